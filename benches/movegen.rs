@@ -0,0 +1,92 @@
+//! 着手の生成の速さを盤面の持ち方ごとに比べるベンチマーク。
+//!
+//! ```cargo bench```で実行する。各サイズで乱数で1局進めた途中の盤面を集め、
+//! ```cnt_reversable```（二次元配列版がもともとの実装）と```legal_moves```、
+//! ランダムに最後まで打つ1局にかかる時間を測る。
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use rand::rngs::SmallRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+use simple_reversi_2::{Backend, BoardState};
+
+/// 1つの測定を繰り返す回数
+const ROUNDS: u32 = 20;
+
+/// 乱数で1局打って、途中の盤面を全て集める
+fn sample_positions(n: usize, backend: Backend, seed: u64) -> Vec<BoardState> {
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let mut bs = BoardState::with_backend(n / 2, false, backend);
+    let mut positions = vec![bs.clone()];
+    loop {
+        let &pos = bs.legal_moves().choose(&mut rng).unwrap();
+        if bs.put(pos.x, pos.y).game_over {
+            break;
+        }
+        positions.push(bs.clone());
+    }
+    positions
+}
+
+/// 乱数で最後まで1局打つ
+fn random_game(n: usize, backend: Backend, rng: &mut SmallRng) {
+    let mut bs = BoardState::with_backend(n / 2, false, backend);
+    loop {
+        let &pos = bs.legal_moves().choose(rng).unwrap();
+        if bs.put(pos.x, pos.y).game_over {
+            break;
+        }
+    }
+    black_box(bs.count_pieces());
+}
+
+/// 1回あたりの時間を表示する
+fn report(name: &str, n: usize, backend: Backend, total: Duration, count: u32) {
+    println!(
+        "{:>16} {:>3}x{:<3} {:>9}: {:>12.1} µs",
+        name,
+        n,
+        n,
+        format!("{:?}", backend),
+        total.as_secs_f64() * 1e6 / count as f64
+    );
+}
+
+fn main() {
+    for &n in &[8, 16, 32] {
+        let mut backends = vec![Backend::Grid, Backend::Wide];
+        if n == 8 {
+            backends.push(Backend::Bitboard);
+        }
+        for &backend in &backends {
+            let positions = sample_positions(n, backend, 2021);
+            let count = ROUNDS * positions.len() as u32;
+
+            let start = Instant::now();
+            for _ in 0..ROUNDS {
+                for bs in &positions {
+                    black_box(bs.cnt_reversable());
+                }
+            }
+            report("cnt_reversable", n, backend, start.elapsed(), count);
+
+            let start = Instant::now();
+            for _ in 0..ROUNDS {
+                for bs in &positions {
+                    black_box(bs.legal_moves());
+                }
+            }
+            report("legal_moves", n, backend, start.elapsed(), count);
+
+            let mut rng = SmallRng::seed_from_u64(2021);
+            let start = Instant::now();
+            for _ in 0..ROUNDS {
+                random_game(n, backend, &mut rng);
+            }
+            report("random_game", n, backend, start.elapsed(), ROUNDS);
+        }
+    }
+}
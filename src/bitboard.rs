@@ -0,0 +1,172 @@
+use crate::boardstate::Turn;
+use crate::moves::Position;
+
+/// 8x8の盤面の一辺の長さ
+pub const BITBOARD_SIZE: usize = 8;
+
+/// 左端（y = 0）の列のマスク
+const COLUMN_LEFT: u64 = 0x0101_0101_0101_0101;
+
+/// 右端（y = 7）の列のマスク
+const COLUMN_RIGHT: u64 = 0x8080_8080_8080_8080;
+
+/// 8方向へのずらし方
+///
+/// 各要素は（ずらすビット数、ずらした後に残すマスク）で、正なら左シフト、負なら右シフト。
+/// 盤面の端をまたいで反対側に回り込んだビットはマスクで消す。
+const DIRECTIONS: [(i32, u64); 8] = [
+    (1, !COLUMN_LEFT),
+    (-1, !COLUMN_RIGHT),
+    (8, !0),
+    (-8, !0),
+    (9, !COLUMN_LEFT),
+    (7, !COLUMN_RIGHT),
+    (-7, !COLUMN_LEFT),
+    (-9, !COLUMN_RIGHT),
+];
+
+/// 8x8の盤面をu64のビット列2つで表す構造体
+///
+/// マス目(x, y)が```8 * x + y```番目のビットに対応する。
+/// blackが黒い駒の置かれたマス目、whiteが白い駒の置かれたマス目。
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Bitboard {
+    pub black: u64,
+    pub white: u64,
+}
+
+/// 1方向にずらす
+fn shift(b: u64, (d, mask): (i32, u64)) -> u64 {
+    if d > 0 {
+        (b << d) & mask
+    } else {
+        (b >> -d) & mask
+    }
+}
+
+/// マス目に対応するビット
+fn bit(x: usize, y: usize) -> u64 {
+    1 << (BITBOARD_SIZE * x + y)
+}
+
+/// 立っているビットをマス目の一覧に直す
+pub fn positions(mut b: u64) -> Vec<Position> {
+    let mut v: Vec<Position> = Vec::with_capacity(b.count_ones() as usize);
+    while b != 0 {
+        let k = b.trailing_zeros() as usize;
+        v.push(Position::new(k / BITBOARD_SIZE, k % BITBOARD_SIZE));
+        b &= b - 1;
+    }
+    v
+}
+
+impl Bitboard {
+    /// 初期配置の盤面を作成する
+    pub fn new() -> Bitboard {
+        Bitboard {
+            black: bit(3, 4) | bit(4, 3),
+            white: bit(3, 3) | bit(4, 4),
+        }
+    }
+
+    /// turnの側の駒と相手の駒
+    fn sides(&self, turn: Turn) -> (u64, u64) {
+        match turn {
+            Turn::Black => (self.black, self.white),
+            Turn::White => (self.white, self.black),
+        }
+    }
+
+    /// 何も置いてないマス目
+    pub fn empty(&self) -> u64 {
+        !(self.black | self.white)
+    }
+
+    /// そのマス目に置かれている駒
+    pub fn get(&self, x: usize, y: usize) -> Option<Turn> {
+        let b = bit(x, y);
+        if self.black & b != 0 {
+            Some(Turn::Black)
+        } else if self.white & b != 0 {
+            Some(Turn::White)
+        } else {
+            None
+        }
+    }
+
+    /// そのマス目の駒を置き換える
+    pub fn set(&mut self, x: usize, y: usize, piece: Option<Turn>) {
+        let b = bit(x, y);
+        self.black &= !b;
+        self.white &= !b;
+        match piece {
+            Some(Turn::Black) => self.black |= b,
+            Some(Turn::White) => self.white |= b,
+            None => {}
+        }
+    }
+
+    /// turnの側が置けるマス目
+    pub fn legal_moves(&self, turn: Turn) -> u64 {
+        let (me, opp) = self.sides(turn);
+        let empty = self.empty();
+        let mut moves: u64 = 0;
+        for &d in DIRECTIONS.iter() {
+            // 自分の駒から相手の駒が続いている範囲を伸ばしていく（間に入るのは最大6個）
+            let mut t = shift(me, d) & opp;
+            for _ in 0..5 {
+                t |= shift(t, d) & opp;
+            }
+            moves |= shift(t, d) & empty;
+        }
+        moves
+    }
+
+    /// turnの側が(x, y)に置いたときに裏返せる駒
+    pub fn flips(&self, turn: Turn, x: usize, y: usize) -> u64 {
+        let (me, opp) = self.sides(turn);
+        let b = bit(x, y);
+        if (me | opp) & b != 0 {
+            return 0;
+        }
+        let mut flipped: u64 = 0;
+        for &d in DIRECTIONS.iter() {
+            let mut f: u64 = 0;
+            let mut t = shift(b, d);
+            while t & opp != 0 {
+                f |= t;
+                t = shift(t, d);
+            }
+            // 自分の駒で挟めたときだけ裏返せる
+            if t & me != 0 {
+                flipped |= f;
+            }
+        }
+        flipped
+    }
+
+    /// turnの側が(x, y)に置いて駒を裏返す
+    ///
+    /// 返り値は裏返した駒。置けるマス目かどうかは確認しない。
+    pub fn put(&mut self, turn: Turn, x: usize, y: usize) -> u64 {
+        let f = self.flips(turn, x, y);
+        let b = bit(x, y);
+        match turn {
+            Turn::Black => {
+                self.black |= b | f;
+                self.white &= !f;
+            }
+            Turn::White => {
+                self.white |= b | f;
+                self.black &= !f;
+            }
+        }
+        f
+    }
+}
+
+impl Default for Bitboard {
+    fn default() -> Self {
+        Bitboard::new()
+    }
+}
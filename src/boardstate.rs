@@ -1,9 +1,18 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::bitboard::{self, Bitboard, BITBOARD_SIZE};
+use crate::moves::{Move, MoveError, MoveOutcome, Position};
+use crate::notation::{self, NotationError};
+use crate::widebitboard::WideBitboard;
+
 /// x方向への微小変化を見る用の配列の代わり
 const fn dx(n: usize) -> i32 {
     match n {
-        0 | 1 | 2 => 1,
+        0..=2 => 1,
         3 | 7 => 0,
-        4 | 5 | 6 => -1,
+        4..=6 => -1,
         _ => 0,
     }
 }
@@ -11,7 +20,7 @@ const fn dx(n: usize) -> i32 {
 /// y方向への微小変化を見る用の配列の代わり
 const fn dy(n: usize) -> i32 {
     match n {
-        2 | 3 | 4 => 1,
+        2..=4 => 1,
         1 | 5 => 0,
         0 | 6 | 7 => -1,
         _ => 0,
@@ -31,39 +40,187 @@ const NO_PIECE: char = '.';
 ///
 /// 駒などを判別するのにも使う。
 /// 値を代入するときにムーヴだと面倒なのでCopyトレイトを実装。
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
-enum Turn {
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Turn {
     White,
     Black,
 }
 
+impl Turn {
+    /// 相手側
+    pub fn opponent(self) -> Turn {
+        match self {
+            Turn::White => Turn::Black,
+            Turn::Black => Turn::White,
+        }
+    }
+
+    /// 駒の文字（char型）
+    pub fn piece(self) -> char {
+        match self {
+            Turn::White => WHITE,
+            Turn::Black => BLACK,
+        }
+    }
+}
+
+/// 盤面のマス目の持ち方
+///
+/// Gridは2次元配列で、各要素は```Option<Turn>```型
+/// （```None```が駒が置かれていない状態、```Some(Turn::White）```が白い駒が置かれている状態、
+/// ```Some(Turn::Black)```が黒い駒が置かれている状態）。どのサイズの盤面でも使える。
+/// Bitboardは8x8の盤面専用のビット列による表現で、着手の生成が速い。
+/// Wideはどのサイズの盤面でも使えるビット列による表現。
+#[derive(Clone, Debug)]
+enum Cells {
+    Grid(Vec<Vec<Option<Turn>>>),
+    Bitboard(Bitboard),
+    Wide(WideBitboard),
+}
+
+/// 盤面のマス目の持ち方の種類
+///
+/// ```BoardState::new```は8x8の盤面では```Bitboard```、それ以外では```Wide```を使う。
+/// ```Grid```は速さの比較や結果の突き合わせのために残してある。
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Backend {
+    Grid,
+    Bitboard,
+    Wide,
+}
+
+impl Cells {
+    /// そのマス目に置かれている駒
+    fn get(&self, x: usize, y: usize) -> Option<Turn> {
+        match self {
+            Cells::Grid(s) => s[x][y],
+            Cells::Bitboard(b) => b.get(x, y),
+            Cells::Wide(b) => b.get(x, y),
+        }
+    }
+
+    /// そのマス目の駒を置き換える
+    fn set(&mut self, x: usize, y: usize, piece: Option<Turn>) {
+        match self {
+            Cells::Grid(s) => s[x][y] = piece,
+            Cells::Bitboard(b) => b.set(x, y, piece),
+            Cells::Wide(b) => b.set(x, y, piece),
+        }
+    }
+}
+
 /// 盤面の情報を持つ構造体
 ///
 /// sizeは盤面のサイズ（高さ=幅）。
-/// stateは2次元配列で、各要素は```Option<Turn>```型
-/// （```None```が駒が置かれていない状態、```Some(Turn::White）```が白い駒が置かれている状態、
-/// ```Some(Turn::Black)```が黒い駒が置かれている状態）。
+/// stateはマス目の状態で、ビット列か2次元配列で持つ（```Backend```を参照）。
 /// turnは今どっちのターンなのかの情報を持つ。
-#[derive(Debug)]
+/// historyはこれまでの着手の履歴、redo_stackは待ったで取り消した手（最後に取り消した手が末尾）。
+#[derive(Clone, Debug)]
 pub struct BoardState {
     size: usize,
-    state: Vec<Vec<Option<Turn>>>,
+    state: Cells,
     turn: Turn,
+    history: Vec<HistoryEntry>,
+    redo_stack: Vec<Position>,
+}
+
+/// 着手の履歴1つ分
+///
+/// playerが着手した側、mvが着手、flippedがその着手で裏返した駒のマス目。
+/// パスの場合はflippedは空。
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HistoryEntry {
+    pub player: Turn,
+    pub mv: Move,
+    pub flipped: Vec<Position>,
 }
 
 impl BoardState {
     /// 新しい盤面を作成する
     pub fn new(n: usize, white_turn: bool) -> BoardState {
+        let backend = if 2 * n == BITBOARD_SIZE {
+            Backend::Bitboard
+        } else {
+            Backend::Wide
+        };
+        BoardState::with_backend(n, white_turn, backend)
+    }
+
+    /// マス目の持ち方を指定して新しい盤面を作成する
+    ///
+    /// ```Backend::Bitboard```は8x8の盤面（n = 4）でしか使えない。
+    pub fn with_backend(n: usize, white_turn: bool, backend: Backend) -> BoardState {
         assert!(n != 0);
-        let mut s: Vec<Vec<Option<Turn>>> = vec![vec![None; 2 * n]; 2 * n];
-        s[n - 1][n - 1] = Some(Turn::White);
-        s[n - 1][n] = Some(Turn::Black);
-        s[n][n - 1] = Some(Turn::Black);
-        s[n][n] = Some(Turn::White);
+        let state = match backend {
+            Backend::Bitboard => {
+                assert!(2 * n == BITBOARD_SIZE);
+                Cells::Bitboard(Bitboard::new())
+            }
+            Backend::Wide => Cells::Wide(WideBitboard::new(2 * n)),
+            Backend::Grid => {
+                let mut s: Vec<Vec<Option<Turn>>> = vec![vec![None; 2 * n]; 2 * n];
+                s[n - 1][n - 1] = Some(Turn::White);
+                s[n - 1][n] = Some(Turn::Black);
+                s[n][n - 1] = Some(Turn::Black);
+                s[n][n] = Some(Turn::White);
+                Cells::Grid(s)
+            }
+        };
         BoardState {
             size: 2 * n,
-            state: s,
+            state,
             turn: if white_turn { Turn::White } else { Turn::Black },
+            history: Vec::new(),
+            redo_stack: Vec::new(),
+        }
+    }
+
+    /// 初期局面（黒番）から棋譜の通りに打った盤面を作成する
+    ///
+    /// 棋譜は```f5d6c3```のような表記（```notation::parse_transcript```を参照）。
+    /// 打てない手があった場合はその手が何手目かを添えて```NotationError```を返す。
+    pub fn from_transcript(n: usize, transcript: &str) -> Result<BoardState, NotationError> {
+        let mut bs = BoardState::new(n, false);
+        for (k, pos) in notation::parse_transcript(transcript)?
+            .into_iter()
+            .enumerate()
+        {
+            bs.try_put(pos.x, pos.y)
+                .map_err(|e| NotationError::IllegalMove(k + 1, pos, e))?;
+        }
+        Ok(bs)
+    }
+
+    /// これまでの着手の履歴（パスも含む）
+    pub fn history(&self) -> &[HistoryEntry] {
+        &self.history
+    }
+
+    /// これまでの着手の棋譜（```f5d6c3```のような表記で、パスは書かない）
+    pub fn transcript(&self) -> String {
+        let moves: Vec<Move> = self.history.iter().map(|entry| entry.mv).collect();
+        notation::format_transcript(&moves)
+    }
+
+    /// 履歴とやり直し用の手を持たない、局面だけの写し
+    ///
+    /// 観戦者に送る局面のように、履歴を持ち回らなくてよいときにクレート内で使う。
+    pub(crate) fn without_history(&self) -> BoardState {
+        BoardState {
+            size: self.size,
+            state: self.state.clone(),
+            turn: self.turn,
+            history: Vec::new(),
+            redo_stack: Vec::new(),
+        }
+    }
+
+    /// マス目の持ち方
+    pub fn backend(&self) -> Backend {
+        match self.state {
+            Cells::Grid(_) => Backend::Grid,
+            Cells::Bitboard(_) => Backend::Bitboard,
+            Cells::Wide(_) => Backend::Wide,
         }
     }
 
@@ -75,18 +232,21 @@ impl BoardState {
     /// 盤面の状態をchar型の二次元配列で出力する
     pub fn show_board(&self) -> Vec<Vec<char>> {
         let n = self.size;
-        let mut v: Vec<Vec<char>> = vec![vec![NO_PIECE; n]; n];
-        for i in 0..n {
-            for j in 0..n {
-                if let Some(t) = &self.state[i][j] {
-                    v[i][j] = match t {
-                        Turn::Black => BLACK,
-                        Turn::White => WHITE,
-                    }
-                }
-            }
-        }
-        v
+        (0..n)
+            .map(|i| {
+                (0..n)
+                    .map(|j| match self.state.get(i, j) {
+                        Some(t) => t.piece(),
+                        None => NO_PIECE,
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// そのマス目に置かれている駒
+    pub fn get(&self, pos: Position) -> Option<Turn> {
+        self.state.get(pos.x, pos.y)
     }
 
     /// 白い駒（char型）
@@ -101,10 +261,12 @@ impl BoardState {
 
     /// どちらのターンかを駒の文字で出力
     pub fn which_turn(&self) -> char {
-        match self.turn {
-            Turn::Black => BLACK,
-            Turn::White => WHITE,
-        }
+        self.turn.piece()
+    }
+
+    /// どちらのターンか
+    pub fn turn(&self) -> Turn {
+        self.turn
     }
 
     /// 白の番かどうか
@@ -116,28 +278,312 @@ impl BoardState {
     pub fn count_pieces(&self) -> ((char, usize), (char, usize)) {
         let mut white_count: usize = 0;
         let mut black_count: usize = 0;
-        let n = self.size;
-        for i in 0..n {
-            for j in 0..n {
-                if let Some(t) = &self.state[i][j] {
+        match &self.state {
+            Cells::Grid(s) => {
+                for t in s.iter().flatten().flatten() {
                     match t {
                         Turn::White => white_count += 1,
                         Turn::Black => black_count += 1,
                     }
                 }
             }
+            Cells::Bitboard(b) => {
+                white_count = b.white.count_ones() as usize;
+                black_count = b.black.count_ones() as usize;
+            }
+            Cells::Wide(b) => {
+                let (w, b) = b.count();
+                white_count = w;
+                black_count = b;
+            }
         }
         ((WHITE, white_count), (BLACK, black_count))
     }
 
+    /// 何も置いてないマス目の個数
+    pub fn count_empties(&self) -> usize {
+        let ((_, white), (_, black)) = self.count_pieces();
+        self.size * self.size - white - black
+    }
+
     /// そこに置いたときに裏返せる駒の個数
     pub fn cnt_reversable(&self) -> Vec<Vec<usize>> {
+        self.cnt_reversable_for(self.turn)
+    }
+
+    /// turnの側がそこに置いたときに裏返せる駒の個数
+    fn cnt_reversable_for(&self, turn: Turn) -> Vec<Vec<usize>> {
+        match &self.state {
+            Cells::Grid(s) => BoardState::grid_cnt_reversable(s, turn),
+            Cells::Bitboard(b) => {
+                let n = self.size;
+                let mut vec: Vec<Vec<usize>> = vec![vec![0; n]; n];
+                for pos in bitboard::positions(b.legal_moves(turn)) {
+                    vec[pos.x][pos.y] = b.flips(turn, pos.x, pos.y).count_ones() as usize;
+                }
+                vec
+            }
+            Cells::Wide(b) => {
+                let n = self.size;
+                let mut vec: Vec<Vec<usize>> = vec![vec![0; n]; n];
+                for pos in b.legal_moves(turn) {
+                    vec[pos.x][pos.y] = b.count_flips(turn, pos.x, pos.y);
+                }
+                vec
+            }
+        }
+    }
+
+    /// 置けるマス目の一覧
+    pub fn legal_moves(&self) -> Vec<Position> {
+        self.legal_moves_for(self.turn)
+    }
+
+    /// turnの側が置けるマス目の一覧（手番でない側の分も調べられる）
+    pub fn legal_moves_for(&self, turn: Turn) -> Vec<Position> {
+        match &self.state {
+            Cells::Grid(_) => {
+                let vec = self.cnt_reversable_for(turn);
+                let mut moves: Vec<Position> = Vec::new();
+                for (i, row) in vec.iter().enumerate() {
+                    for (j, &c) in row.iter().enumerate() {
+                        if c > 0 {
+                            moves.push(Position::new(i, j));
+                        }
+                    }
+                }
+                moves
+            }
+            Cells::Bitboard(b) => bitboard::positions(b.legal_moves(turn)),
+            Cells::Wide(b) => b.legal_moves(turn),
+        }
+    }
+
+    /// turnの側が置けるかどうかを判定
+    fn puttable_for(&self, turn: Turn) -> bool {
+        match &self.state {
+            Cells::Grid(s) => BoardState::grid_cnt_reversable(s, turn)
+                .iter()
+                .any(|row| row.iter().any(|&c| c > 0)),
+            Cells::Bitboard(b) => b.legal_moves(turn) != 0,
+            Cells::Wide(b) => b.has_legal_move(turn),
+        }
+    }
+
+    /// 両者ともに置けるマスがなく、ゲームが終わっているかどうか
+    pub fn is_game_over(&self) -> bool {
+        !self.puttable_for(Turn::White) && !self.puttable_for(Turn::Black)
+    }
+
+    /// マス目に駒を置く操作
+    ///
+    /// 返り値は裏返した駒、相手がパスになったかどうか、ゲームが終わったかどうかの情報。
+    /// 置けないマス目を指定した場合はpanicする（panicさせたくない場合は```try_put```を使う）。
+    pub fn put(&mut self, i: usize, j: usize) -> MoveOutcome {
+        match self.try_put(i, j) {
+            Ok(outcome) => outcome,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// マス目に駒を置く操作（失敗しうる版）
+    ///
+    /// 置けないマス目を指定した場合は盤面を変更せずに```MoveError```を返す。
+    pub fn try_put(&mut self, i: usize, j: usize) -> Result<MoveOutcome, MoveError> {
         let n = self.size;
+        if self.is_game_over() {
+            return Err(MoveError::GameOver);
+        }
+        if i >= n || j >= n {
+            return Err(MoveError::OutOfBounds);
+        }
+        if self.state.get(i, j).is_some() {
+            return Err(MoveError::Occupied);
+        }
+        let no_flip = match &self.state {
+            Cells::Grid(_) => self.cnt_reversable()[i][j] == 0,
+            Cells::Bitboard(b) => b.flips(self.turn, i, j) == 0,
+            Cells::Wide(b) => b.count_flips(self.turn, i, j) == 0,
+        };
+        if no_flip {
+            return Err(MoveError::NoFlip);
+        }
+
+        // 新しく手を打ったらやり直し用の手は捨てる
+        self.redo_stack.clear();
+        Ok(self.apply(i, j))
+    }
+
+    /// 置けることが確認済みのマス目に駒を置き、履歴に記録する
+    ///
+    /// 探索などで何度も進めたり戻したりするとき用に、確認を省いてクレート内に公開している。
+    pub(crate) fn apply(&mut self, i: usize, j: usize) -> MoveOutcome {
+        let player = self.turn;
+        let flipped = match &mut self.state {
+            Cells::Grid(s) => BoardState::grid_put(s, player, i, j),
+            Cells::Bitboard(b) => bitboard::positions(b.put(player, i, j)),
+            Cells::Wide(b) => b.put(player, i, j),
+        };
+
+        // 相手が置けるならターンを交代、置けないなら相手はパス
+        let opponent = player.opponent();
+        let opponent_passed = !self.puttable_for(opponent);
+        if !opponent_passed {
+            self.turn = opponent;
+        }
+
+        // 相手がパスで自分も置けないならゲーム終了
+        let game_over = opponent_passed && !self.puttable_for(player);
+
+        // 履歴に記録（パスになった場合はパスも記録）
+        self.history.push(HistoryEntry {
+            player,
+            mv: Move::Put(Position::new(i, j)),
+            flipped: flipped.clone(),
+        });
+        if opponent_passed && !game_over {
+            self.history.push(HistoryEntry {
+                player: opponent,
+                mv: Move::Pass,
+                flipped: Vec::new(),
+            });
+        }
+
+        MoveOutcome {
+            player,
+            position: Position::new(i, j),
+            flipped,
+            opponent_passed: opponent_passed && !game_over,
+            game_over,
+        }
+    }
+
+    /// 最後に置いた1手を取り消す（待った）
+    ///
+    /// その手の後のパスもまとめて取り消す。
+    /// 返り値は取り消した手のマス目で、取り消せる手がない場合は```None```。
+    pub fn undo(&mut self) -> Option<Position> {
+        let pos = self.take_back()?;
+        self.redo_stack.push(pos);
+        Some(pos)
+    }
+
+    /// 手番だけを相手に渡す
+    ///
+    /// 手番の側が置けない局面でパスを読むときに、探索などからクレート内で使う。
+    pub(crate) fn switch_turn(&mut self) {
+        self.turn = self.turn.opponent();
+    }
+
+    /// 最後に置いた1手とその後のパスを盤面と履歴から取り除く
+    ///
+    /// ```undo```と違い、やり直し用の手には積まない。
+    pub(crate) fn take_back(&mut self) -> Option<Position> {
+        // 末尾のパスは取り除いておく
+        while let Some(HistoryEntry { mv: Move::Pass, .. }) = self.history.last() {
+            self.history.pop();
+        }
+        let entry = self.history.pop()?;
+        let pos = match entry.mv {
+            Move::Put(pos) => pos,
+            Move::Pass => unreachable!(),
+        };
+
+        // 置いた駒を取り除き、裏返した駒を元に戻す
+        self.state.set(pos.x, pos.y, None);
+        for p in &entry.flipped {
+            self.state.set(p.x, p.y, Some(entry.player.opponent()));
+        }
+        self.turn = entry.player;
+        Some(pos)
+    }
+
+    /// 手番の側のパスを履歴に記録し、手番を相手に渡す
+    ///
+    /// ```take_back_pass```で取り除いたパスを記録し直すために、GTPなどからクレート内で使う。
+    pub(crate) fn pass(&mut self) {
+        self.history.push(HistoryEntry {
+            player: self.turn,
+            mv: Move::Pass,
+            flipped: Vec::new(),
+        });
+        self.switch_turn();
+    }
+
+    /// 履歴の最後がパスならそれだけを取り除き、手番をパスした側に戻す
+    ///
+    /// 取り除いた後はその側が置けない局面になる。最後がパスでなければ何もせず```false```を返す。
+    pub(crate) fn take_back_pass(&mut self) -> bool {
+        match self.history.last() {
+            Some(&HistoryEntry {
+                mv: Move::Pass,
+                player,
+                ..
+            }) => {
+                self.history.pop();
+                self.turn = player;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// 待ったで取り消した手をもう一度打つ
+    ///
+    /// 返り値は打ち直した結果で、やり直せる手がない場合は```None```。
+    pub fn redo(&mut self) -> Option<MoveOutcome> {
+        let pos = self.redo_stack.pop()?;
+        Some(self.apply(pos.x, pos.y))
+    }
+
+    /// depth手先までの局面の数を数える（perft）
+    ///
+    /// 着手の生成が正しいかを確かめるためのもの。
+    /// パスも1手として数え、depth手に届く前にゲームが終わった局面はその局面を1つと数える。
+    pub fn perft(&self, depth: usize) -> u64 {
+        let mut bs = self.clone();
+        bs.perft_inner(depth)
+    }
+
+    /// perftの本体（盤面を進めては戻しながら数える）
+    fn perft_inner(&mut self, depth: usize) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        let moves = self.legal_moves();
+        if moves.is_empty() {
+            // 手番の側が置けない局面ではパスを1手として進める
+            if self.is_game_over() {
+                return 1;
+            }
+            self.switch_turn();
+            let nodes = self.perft_inner(depth - 1);
+            self.switch_turn();
+            return nodes;
+        }
+        let mut nodes: u64 = 0;
+        for pos in moves {
+            let outcome = self.apply(pos.x, pos.y);
+            nodes += if outcome.game_over || depth == 1 {
+                1
+            } else if outcome.opponent_passed {
+                // 相手のパスで1手使う
+                self.perft_inner(depth - 2)
+            } else {
+                self.perft_inner(depth - 1)
+            };
+            self.take_back();
+        }
+        nodes
+    }
+
+    /// turnの側がそこに置いたときに裏返せる駒の個数（二次元配列版）
+    fn grid_cnt_reversable(s: &[Vec<Option<Turn>>], turn: Turn) -> Vec<Vec<usize>> {
+        let n = s.len();
         let mut vec: Vec<Vec<usize>> = vec![vec![0; n]; n];
-        let s = &self.state;
         for i in 0..n {
             for j in 0..n {
-                if let Some(_) = s[i][j] {
+                if s[i][j].is_some() {
                     // もう置いてあるマスはスルー
                     continue;
                 }
@@ -158,7 +604,7 @@ impl BoardState {
                     // 隣のマスが空ならもう処理はいらない
                     if let Some(t) = s[new_x][new_y] {
                         // 隣のマスが自分と同じ色ならもう処理はいらない
-                        if t == self.turn {
+                        if t == turn {
                             continue;
                         }
                         // 隣のマスが自分と違う色のときだけ進んで行く
@@ -174,14 +620,14 @@ impl BoardState {
                             let new_y: usize = new_y as usize;
 
                             // 空のマスに着いたら終了
-                            if let None = s[new_x][new_y] {
+                            if s[new_x][new_y].is_none() {
                                 break;
                             }
 
                             // 自分と同じ色が再び現れたらこのときだけ裏返せるので
                             // 裏返せる枚数をカウントアップ
                             if let Some(t) = s[new_x][new_y] {
-                                if t == self.turn {
+                                if t == turn {
                                     vec[i][j] += l;
                                     break;
                                 }
@@ -194,31 +640,13 @@ impl BoardState {
         vec
     }
 
-    /// 置けるかどうかを判定
-    fn puttable(&self) -> bool {
-        let n = self.size;
-        let vec = self.cnt_reversable();
-        let mut flag: bool = false;
-        for i in 0..n {
-            for j in 0..n {
-                if vec[i][j] > 0 {
-                    flag = true;
-                }
-            }
-        }
-        flag
-    }
-
-    /// マス目に駒を置く操作
+    /// playerの側が(i, j)に駒を置いて裏返す（二次元配列版）
     ///
-    /// 返り値は、ゲームを続けられる場合true、両者ともに置けるマスがない場合にfalse。
-    pub fn put(&mut self, i: usize, j: usize) -> bool {
-        let n = self.size;
-        assert!(i < n && j < n);
-        let vec = &self.cnt_reversable();
-        assert!(vec[i][j] > 0);
-        let s = &mut self.state;
-        s[i][j] = Some(self.turn);
+    /// 返り値は裏返した駒のマス目。
+    fn grid_put(s: &mut [Vec<Option<Turn>>], player: Turn, i: usize, j: usize) -> Vec<Position> {
+        let n = s.len();
+        let mut flipped: Vec<Position> = Vec::new();
+        s[i][j] = Some(player);
         for k in 0..8 {
             // 進む方向ごとに判定
 
@@ -236,7 +664,7 @@ impl BoardState {
             // 隣のマスが空ならもう処理はいらない
             if let Some(t) = s[new_x][new_y] {
                 // 隣のマスが自分と同じ色ならもう処理はいらない
-                if t == self.turn {
+                if t == player {
                     continue;
                 }
                 // 隣のマスが自分と違う色のときだけ進んで行く
@@ -252,18 +680,20 @@ impl BoardState {
                     let new_y: usize = new_y as usize;
 
                     // 空のマスに着いたら終了
-                    if let None = s[new_x][new_y] {
+                    if s[new_x][new_y].is_none() {
                         break;
                     }
 
                     // 自分と同じ色が再び現れたらこのときだけ裏返せるので
                     // 実際に裏返していく
                     if let Some(t) = s[new_x][new_y] {
-                        if t == self.turn {
+                        if t == player {
                             // 間の駒を裏返していく処理
                             for m in 1..=l {
-                                s[(i as i32 + m as i32 * dx(k)) as usize]
-                                    [(j as i32 + m as i32 * dy(k)) as usize] = Some(self.turn);
+                                let x = (i as i32 + m as i32 * dx(k)) as usize;
+                                let y = (j as i32 + m as i32 * dy(k)) as usize;
+                                s[x][y] = Some(player);
+                                flipped.push(Position::new(x, y));
                             }
                             break;
                         }
@@ -272,36 +702,102 @@ impl BoardState {
             }
         }
 
-        // ターンを交代
-        self.turn = if self.turn == Turn::White {
-            Turn::Black
-        } else {
-            Turn::White
-        };
+        flipped
+    }
 
-        // 置けるならtrueを返して終了
-        if BoardState::puttable(&self) {
-            return true;
-        }
+    /// マスの範囲内（0..n）かどうかを判定
+    fn in_range(z: i32, n: usize) -> bool {
+        z >= 0 && z < n as i32
+    }
+}
 
-        // 置けないならもう一度ターンを交代
-        self.turn = if self.turn == Turn::White {
-            Turn::Black
-        } else {
-            Turn::White
-        };
+/// 局面の文字列を読めなかった理由
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseBoardError {
+    /// 盤面と手番が1つの空白で区切られていない
+    Format,
+    /// 盤面が4以上の偶数マスの正方形でない
+    Size,
+    /// 駒として読めない文字があった（行、列は0始まり）
+    Cell(usize, usize, char),
+    /// 手番として読めない文字列だった
+    Turn(String),
+}
 
-        // 今度は置けるならtrueを返す
-        if BoardState::puttable(&self) {
-            true
-        } else {
-            // 置けないならfalseを返す
-            false
+impl fmt::Display for ParseBoardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseBoardError::Format => write!(f, "盤面と手番を空白で区切ってください"),
+            ParseBoardError::Size => write!(f, "盤面は4以上の偶数マスの正方形にしてください"),
+            ParseBoardError::Cell(x, y, c) => {
+                write!(f, "{}行{}列目の{}は駒として読めません", x + 1, y + 1, c)
+            }
+            ParseBoardError::Turn(s) => write!(f, "{}は手番として読めません", s),
         }
     }
+}
 
-    /// マスの範囲内（0..n）かどうかを判定
-    fn in_range(z: i32, n: usize) -> bool {
-        z >= 0 && z < n as i32
+impl Error for ParseBoardError {}
+
+/// 駒の文字から駒を読む
+fn piece_from_char(c: char) -> Option<Option<Turn>> {
+    match c {
+        WHITE => Some(Some(Turn::White)),
+        BLACK => Some(Some(Turn::Black)),
+        NO_PIECE => Some(None),
+        _ => None,
+    }
+}
+
+impl fmt::Display for BoardState {
+    /// 局面を1行の文字列で表示する
+    ///
+    /// ```show_board```の各行を```/```でつなぎ、空白を挟んで手番の駒を書く
+    /// （例：```..../.o#./.#o./.... #```）。履歴は含まない。
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rows: Vec<String> = self
+            .show_board()
+            .iter()
+            .map(|row| row.iter().collect())
+            .collect();
+        write!(f, "{} {}", rows.join("/"), self.which_turn())
+    }
+}
+
+impl FromStr for BoardState {
+    type Err = ParseBoardError;
+
+    /// ```Display```で表示した形式の局面を読む
+    ///
+    /// 履歴のない盤面になるので、読んだ局面より前には待ったできない。
+    /// 手番の側が置けずに相手だけが置ける局面は、```put```と同じように相手の番にする。
+    fn from_str(s: &str) -> Result<BoardState, ParseBoardError> {
+        let mut parts = s.split_whitespace();
+        let (board, turn) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(board), Some(turn), None) => (board, turn),
+            _ => return Err(ParseBoardError::Format),
+        };
+        let rows: Vec<Vec<char>> = board.split('/').map(|row| row.chars().collect()).collect();
+        let n = rows.len();
+        if n < 4 || !n.is_multiple_of(2) || rows.iter().any(|row| row.len() != n) {
+            return Err(ParseBoardError::Size);
+        }
+        let white_turn = match turn.chars().collect::<Vec<char>>()[..] {
+            [WHITE] => true,
+            [BLACK] => false,
+            _ => return Err(ParseBoardError::Turn(turn.to_string())),
+        };
+
+        let mut bs = BoardState::new(n / 2, white_turn);
+        for (x, row) in rows.iter().enumerate() {
+            for (y, &c) in row.iter().enumerate() {
+                let piece = piece_from_char(c).ok_or(ParseBoardError::Cell(x, y, c))?;
+                bs.state.set(x, y, piece);
+            }
+        }
+        if !bs.puttable_for(bs.turn) && bs.puttable_for(bs.turn.opponent()) {
+            bs.switch_turn();
+        }
+        Ok(bs)
     }
 }
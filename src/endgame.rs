@@ -0,0 +1,133 @@
+use crate::boardstate::BoardState;
+use crate::moves::Position;
+use crate::search::disc_difference;
+
+/// 並べ替えにかかる手間の方が大きくなるので、空きマスがこれ以下なら手の順番を並べ替えない
+const ORDERING_MIN_EMPTIES: usize = 6;
+
+/// 勝ち負け
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum GameResult {
+    Win,
+    Loss,
+    Draw,
+}
+
+/// 終盤の読み切りの結果
+///
+/// best_moveが最善手（置ける手がない場合は```None```）、
+/// disc_diffが両者最善を尽くしたときの終局時の石差（手番の側から見た値、空きマスはどちらにも数えない）、
+/// resultが手番の側から見た勝ち負け、nodesが調べた局面の数。
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct EndgameResult {
+    pub best_move: Option<Position>,
+    pub disc_diff: i32,
+    pub result: GameResult,
+    pub nodes: u64,
+}
+
+/// 読み切り中の状態（nodesは調べた局面の数）
+struct Solver {
+    nodes: u64,
+}
+
+impl Solver {
+    /// posに置いた後の局面を読み切って、置く側から見た石差を返す
+    fn score_move(&mut self, bs: &mut BoardState, pos: Position, alpha: i32, beta: i32) -> i32 {
+        let me = bs.turn();
+        bs.apply(pos.x, pos.y);
+        // 相手がパスになった（または終局した）ときは手番が自分のままなので符号を反転しない
+        let score = if bs.turn() == me {
+            self.solve(bs, alpha, beta)
+        } else {
+            -self.solve(bs, -beta, -alpha)
+        };
+        bs.take_back();
+        score
+    }
+
+    /// 相手の置けるマス目が少なくなる手から順に並べる
+    fn ordered_moves(&mut self, bs: &mut BoardState) -> Vec<Position> {
+        let mut moves = bs.legal_moves();
+        if bs.count_empties() > ORDERING_MIN_EMPTIES {
+            let mut keyed: Vec<(usize, Position)> = moves
+                .iter()
+                .map(|&pos| {
+                    bs.apply(pos.x, pos.y);
+                    let mobility = bs.legal_moves().len();
+                    bs.take_back();
+                    (mobility, pos)
+                })
+                .collect();
+            keyed.sort_by_key(|&(mobility, _)| mobility);
+            moves = keyed.into_iter().map(|(_, pos)| pos).collect();
+        }
+        moves
+    }
+
+    /// アルファベータ法で終局まで読み切り、手番の側から見た石差を返す
+    fn solve(&mut self, bs: &mut BoardState, mut alpha: i32, beta: i32) -> i32 {
+        self.nodes += 1;
+        let moves = self.ordered_moves(bs);
+        if moves.is_empty() {
+            if bs.is_game_over() {
+                return disc_difference(bs, bs.turn());
+            }
+            // 手番の側だけが置けない局面ではパスして相手の番を読む
+            bs.switch_turn();
+            let score = -self.solve(bs, -beta, -alpha);
+            bs.switch_turn();
+            return score;
+        }
+        let mut best = -((bs.get_size() * bs.get_size()) as i32);
+        for pos in moves {
+            let score = self.score_move(bs, pos, alpha, beta);
+            best = best.max(score);
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+        best
+    }
+}
+
+/// 手番の側から見て、両者最善を尽くしたときの勝ち負けと石差を終局まで読み切って求める
+///
+/// 空きマスの数に対して指数的に時間がかかるので、空きマスが少ない局面で使う。
+pub fn solve(bs: &BoardState) -> EndgameResult {
+    let mut board = bs.clone();
+    let mut solver = Solver { nodes: 0 };
+    // 石差の絶対値はマス目の数を超えないので、それより広い範囲から読み始める
+    let bound = (bs.get_size() * bs.get_size()) as i32 + 1;
+    let moves = solver.ordered_moves(&mut board);
+
+    let (best_move, disc_diff) = if moves.is_empty() {
+        (None, solver.solve(&mut board, -bound, bound))
+    } else {
+        let mut best_move = moves[0];
+        let mut alpha = -bound;
+        for &pos in &moves {
+            let score = solver.score_move(&mut board, pos, alpha, bound);
+            if score > alpha {
+                best_move = pos;
+                alpha = score;
+            }
+        }
+        (Some(best_move), alpha)
+    };
+
+    let result = if disc_diff > 0 {
+        GameResult::Win
+    } else if disc_diff < 0 {
+        GameResult::Loss
+    } else {
+        GameResult::Draw
+    };
+    EndgameResult {
+        best_move,
+        disc_diff,
+        result,
+        nodes: solver.nodes,
+    }
+}
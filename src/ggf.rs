@@ -0,0 +1,371 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::boardstate::{BoardState, Turn};
+use crate::moves::{Move, MoveError, Position};
+
+/// GGFで黒い駒を表す文字
+const GGF_BLACK: char = '*';
+
+/// GGFで白い駒を表す文字
+const GGF_WHITE: char = 'O';
+
+/// GGFで何も置いていないマス目を表す文字
+const GGF_EMPTY: char = '-';
+
+/// GGF（Generic Game Format）の棋譜1局分
+///
+/// sizeは盤面のサイズ（```BO[]```）、startは初期局面（```BO[]```の盤面と手番）、
+/// black/whiteは対局者の名前（```PB[]```/```PW[]```）、
+/// resultは黒から見た石差（```RE[]```、空きマスは勝った側に数える、書かれていなければ```None```）、
+/// movesは着手の列（パスも含む）。
+#[derive(Clone, Debug)]
+pub struct GgfGame {
+    pub size: usize,
+    pub start: BoardState,
+    pub black: String,
+    pub white: String,
+    pub result: Option<i32>,
+    pub moves: Vec<Move>,
+}
+
+/// GGFの棋譜を読めなかった理由
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum GgfError {
+    /// 書式が正しくない（読めなかった部分を添える）
+    Syntax(String),
+    /// 必要な項目がなかった
+    Missing(&'static str),
+    /// 盤面に打てない手があった（何手目かは1始まり）
+    IllegalMove(usize, Position, MoveError),
+    /// 手番でない側の手やパスできない局面でのパスがあった（何手目かは1始まり）
+    WrongTurn(usize),
+}
+
+impl fmt::Display for GgfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GgfError::Syntax(s) => write!(f, "GGFとして読めません：{}", s),
+            GgfError::Missing(key) => write!(f, "{}[]の項目がありません", key),
+            GgfError::IllegalMove(k, pos, e) => {
+                write!(f, "{}手目の{}には打てません（{}）", k, pos, e)
+            }
+            GgfError::WrongTurn(k) => write!(f, "{}手目が手番と合いません", k),
+        }
+    }
+}
+
+impl Error for GgfError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            GgfError::IllegalMove(_, _, e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// 駒をGGFの文字にする
+fn ggf_piece(piece: Option<Turn>) -> char {
+    match piece {
+        Some(Turn::Black) => GGF_BLACK,
+        Some(Turn::White) => GGF_WHITE,
+        None => GGF_EMPTY,
+    }
+}
+
+/// ```BO[]```の中身（サイズ、各行、手番）から局面を作る
+///
+/// 返り値は局面と```BO[]```に書かれていた手番。書かれていた側が置けない局面では、
+/// 局面の手番は相手になっている（```BoardState```の```FromStr```を参照）。
+fn parse_board(value: &str) -> Result<(BoardState, Turn), GgfError> {
+    let syntax_error = || GgfError::Syntax(format!("BO[{}]", value));
+    let mut tokens = value.split_whitespace();
+    let size: usize = tokens
+        .next()
+        .and_then(|t| t.parse().ok())
+        .ok_or_else(syntax_error)?;
+    let mut cells: Vec<char> = tokens.flat_map(|t| t.chars()).collect();
+    let turn = cells.pop().ok_or_else(syntax_error)?;
+    if cells.len() != size * size {
+        return Err(syntax_error());
+    }
+
+    // BoardStateの局面の形式に直して読む
+    let rows: Vec<String> = cells
+        .chunks(size)
+        .map(|row| {
+            row.iter()
+                .map(|&c| match c {
+                    GGF_BLACK => Ok(Turn::Black.piece()),
+                    GGF_WHITE => Ok(Turn::White.piece()),
+                    GGF_EMPTY => Ok('.'),
+                    _ => Err(syntax_error()),
+                })
+                .collect()
+        })
+        .collect::<Result<_, _>>()?;
+    let turn = match turn {
+        GGF_BLACK => Turn::Black,
+        GGF_WHITE => Turn::White,
+        _ => return Err(syntax_error()),
+    };
+    let bs = format!("{} {}", rows.join("/"), turn.piece())
+        .parse()
+        .map_err(|_| syntax_error())?;
+    Ok((bs, turn))
+}
+
+/// ```RE[]```の中身（黒から見た石差、```+2.000```や```-4.000:r```のような形）を読む
+///
+/// 終わっていない対局の```?```は```None```にする。
+fn parse_result(value: &str) -> Result<Option<i32>, GgfError> {
+    let score = value.split(':').next().unwrap_or("").trim();
+    if score == "?" {
+        return Ok(None);
+    }
+    score
+        .parse::<f64>()
+        .map(|r| Some(r.round() as i32))
+        .map_err(|_| GgfError::Syntax(format!("RE[{}]", value)))
+}
+
+/// ```B[]```/```W[]```の中身（```f5```、```f5//1.00```、```PA```など）を読む
+fn parse_move(value: &str) -> Result<Move, GgfError> {
+    let square = value.split('/').next().unwrap_or("").trim();
+    if square.eq_ignore_ascii_case("pa") || square.eq_ignore_ascii_case("pass") {
+        return Ok(Move::Pass);
+    }
+    square
+        .parse()
+        .map(Move::Put)
+        .map_err(|_| GgfError::Syntax(format!("[{}]", value)))
+}
+
+/// ```(;```から```;)```までの1局分を```KEY[value]```の組に分ける
+///
+/// 値の中の```]```と```\```は```\```でエスケープされている。
+fn parse_properties(s: &str) -> Result<Vec<(String, String)>, GgfError> {
+    let mut properties: Vec<(String, String)> = Vec::new();
+    let mut chars = s.chars().peekable();
+    loop {
+        while chars.peek().is_some_and(|c| c.is_whitespace()) {
+            chars.next();
+        }
+        let key: String =
+            std::iter::from_fn(|| chars.next_if(|c| c.is_ascii_alphanumeric())).collect();
+        if key.is_empty() {
+            if chars.peek().is_none() {
+                return Ok(properties);
+            }
+            let rest: String = chars.collect();
+            return Err(GgfError::Syntax(rest));
+        }
+        if chars.next() != Some('[') {
+            return Err(GgfError::Syntax(key));
+        }
+        let mut value = String::new();
+        loop {
+            match chars.next() {
+                Some('\\') => value.extend(chars.next()),
+                Some(']') => break,
+                Some(c) => value.push(c),
+                None => return Err(GgfError::Syntax(format!("{}[{}", key, value))),
+            }
+        }
+        properties.push((key, value));
+    }
+}
+
+/// 値に含まれる```]```と```\```をエスケープする
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace(']', "\\]")
+}
+
+impl GgfGame {
+    /// 盤面の履歴から棋譜を作る
+    ///
+    /// 初期局面は履歴を最初まで戻して求める。ゲームが終わっていれば結果も書く
+    /// （GGFやWTHORの決まりに合わせて、空きマスは勝った側の石として数える）。
+    pub fn from_board(bs: &BoardState, black: &str, white: &str) -> GgfGame {
+        let mut start = bs.clone();
+        while start.take_back().is_some() {}
+        let result = if bs.is_game_over() {
+            let ((_, white_count), (_, black_count)) = bs.count_pieces();
+            let diff = black_count as i32 - white_count as i32;
+            // 空きマスは勝った側の石として数える
+            let empties = bs.count_empties() as i32;
+            Some(diff + diff.signum() * empties)
+        } else {
+            None
+        };
+        GgfGame {
+            size: bs.get_size(),
+            start,
+            black: black.to_string(),
+            white: white.to_string(),
+            result,
+            moves: bs.history().iter().map(|entry| entry.mv).collect(),
+        }
+    }
+
+    /// 初期局面から着手を並べ直して盤面を作る
+    ///
+    /// 着手は黒白交互に並んでいるものとして、手番と合っているか、打てる手かを確かめる。
+    /// パスは```BoardState```が自動で処理するので、その側が本当にパスになっているかだけ確かめる。
+    pub fn to_board(&self) -> Result<BoardState, GgfError> {
+        let mut bs = self.start.clone();
+        let mut mover = bs.turn();
+        for (k, mv) in self.moves.iter().enumerate() {
+            match *mv {
+                Move::Put(pos) => {
+                    if bs.turn() != mover {
+                        return Err(GgfError::WrongTurn(k + 1));
+                    }
+                    bs.try_put(pos.x, pos.y)
+                        .map_err(|e| GgfError::IllegalMove(k + 1, pos, e))?;
+                }
+                Move::Pass => {
+                    if bs.turn() == mover {
+                        return Err(GgfError::WrongTurn(k + 1));
+                    }
+                }
+            }
+            mover = mover.opponent();
+        }
+        Ok(bs)
+    }
+}
+
+impl fmt::Display for GgfGame {
+    /// 1局分を1行のGGFで表示する
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "(;GM[Othello]PC[simple-reversi-2]")?;
+        write!(f, "PB[{}]PW[{}]", escape(&self.black), escape(&self.white))?;
+        if let Some(r) = self.result {
+            write!(f, "RE[{:+}.000]", r)?;
+        }
+        write!(f, "TY[{}]BO[{}", self.size, self.size)?;
+        for x in 0..self.size {
+            let row: String = (0..self.size)
+                .map(|y| ggf_piece(self.start.get(Position::new(x, y))))
+                .collect();
+            write!(f, " {}", row)?;
+        }
+        write!(f, " {}]", ggf_piece(Some(self.start.turn())))?;
+
+        let mut mover = self.start.turn();
+        for mv in &self.moves {
+            let color = if mover == Turn::Black { 'B' } else { 'W' };
+            match mv {
+                Move::Put(pos) => write!(f, "{}[{}]", color, pos)?,
+                Move::Pass => write!(f, "{}[PA]", color)?,
+            }
+            mover = mover.opponent();
+        }
+        write!(f, ";)")
+    }
+}
+
+impl FromStr for GgfGame {
+    type Err = GgfError;
+
+    /// 1局分のGGFを読む（前後の空白は無視する）
+    fn from_str(s: &str) -> Result<GgfGame, GgfError> {
+        let body = s
+            .trim()
+            .strip_prefix("(;")
+            .and_then(|s| s.strip_suffix(";)"))
+            .ok_or_else(|| GgfError::Syntax(s.trim().chars().take(20).collect()))?;
+
+        let mut start: Option<(BoardState, Turn)> = None;
+        let mut black = String::new();
+        let mut white = String::new();
+        let mut result: Option<i32> = None;
+        let mut moves: Vec<Move> = Vec::new();
+        // 着手ごとの色（パスも書かれるので、初期局面の手番から交互になっているはず）
+        let mut colors: Vec<Turn> = Vec::new();
+        for (key, value) in parse_properties(body)? {
+            match key.as_str() {
+                "GM" if !value.eq_ignore_ascii_case("othello") => {
+                    return Err(GgfError::Syntax(format!("GM[{}]", value)));
+                }
+                "BO" => start = Some(parse_board(&value)?),
+                "PB" => black = value,
+                "PW" => white = value,
+                "RE" => result = parse_result(&value)?,
+                "B" | "W" => {
+                    moves.push(parse_move(&value)?);
+                    colors.push(if key == "B" { Turn::Black } else { Turn::White });
+                }
+                // その他の項目（日時や持ち時間など）は読み飛ばす
+                _ => {}
+            }
+        }
+        let (start, declared) = start.ok_or(GgfError::Missing("BO"))?;
+        // 書かれた手番の側が置けずに相手番から始まる局面では、最初に書かれたそのパスを読み飛ばす
+        let mut skipped = 0;
+        if start.turn() != declared
+            && colors.first() == Some(&declared)
+            && moves.first() == Some(&Move::Pass)
+        {
+            moves.remove(0);
+            colors.remove(0);
+            skipped = 1;
+        }
+        let mut mover = start.turn();
+        for (k, &color) in colors.iter().enumerate() {
+            if color != mover {
+                return Err(GgfError::WrongTurn(k + 1 + skipped));
+            }
+            mover = mover.opponent();
+        }
+        Ok(GgfGame {
+            size: start.get_size(),
+            start,
+            black,
+            white,
+            result,
+            moves,
+        })
+    }
+}
+
+/// 1局分の終わりの```;)```の位置を探す
+///
+/// ```[]```の値の中（```\]```でエスケープされた```]```も含む）にある```;)```は終わりとみなさない。
+fn find_game_end(s: &str) -> Option<usize> {
+    let mut in_value = false;
+    let mut escaped = false;
+    let mut prev = ' ';
+    for (k, c) in s.char_indices() {
+        if in_value {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == ']' {
+                in_value = false;
+            }
+        } else if c == '[' {
+            in_value = true;
+        } else if prev == ';' && c == ')' {
+            return Some(k - 1);
+        }
+        prev = c;
+    }
+    None
+}
+
+/// 複数の棋譜が並んだGGFを読む（データベースのファイルなど）
+pub fn parse_games(s: &str) -> Result<Vec<GgfGame>, GgfError> {
+    let mut games: Vec<GgfGame> = Vec::new();
+    let mut rest = s.trim_start();
+    while !rest.is_empty() {
+        let end =
+            find_game_end(rest).ok_or_else(|| GgfError::Syntax(rest.chars().take(20).collect()))?;
+        games.push(rest[..end + 2].parse()?);
+        rest = rest[end + 2..].trim_start();
+    }
+    Ok(games)
+}
@@ -0,0 +1,310 @@
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+use crate::boardstate::{BoardState, Turn};
+use crate::moves::{Move, MoveError, Position};
+use crate::notation::column_name;
+use crate::search::{search, Level, SearchLimits};
+
+/// ```name```で返す名前
+const ENGINE_NAME: &str = "simple-reversi-2";
+
+/// 対応しているGTPのバージョン
+const PROTOCOL_VERSION: u32 = 2;
+
+/// 受け付ける命令（```list_commands```で返す順）
+const COMMANDS: [&str; 13] = [
+    "protocol_version",
+    "name",
+    "version",
+    "known_command",
+    "list_commands",
+    "quit",
+    "boardsize",
+    "clear_board",
+    "play",
+    "genmove",
+    "undo",
+    "showboard",
+    "final_score",
+];
+
+/// GTPの命令を実行できなかった理由
+///
+/// ```Display```はGTPで慣例になっている英語のエラーメッセージ（```? ```の後に書くもの）を返す。
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum GtpError {
+    /// 知らない命令
+    UnknownCommand(String),
+    /// 引数が足りない、または読めない
+    Syntax,
+    /// 盤面のサイズとして使えない（4以上の偶数でない）
+    UnacceptableSize,
+    /// 手番でない側の手
+    WrongColor,
+    /// 打てない手
+    IllegalMove(MoveError),
+    /// 置けるマス目があるのにパスしようとした
+    IllegalPass,
+    /// 取り消せる手がない
+    CannotUndo,
+}
+
+impl fmt::Display for GtpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GtpError::UnknownCommand(_) => write!(f, "unknown command"),
+            GtpError::Syntax => write!(f, "syntax error"),
+            GtpError::UnacceptableSize => write!(f, "unacceptable size"),
+            GtpError::WrongColor => write!(f, "illegal move (not this color's turn)"),
+            GtpError::IllegalMove(_) => write!(f, "illegal move"),
+            GtpError::IllegalPass => write!(f, "illegal move (pass with legal moves)"),
+            GtpError::CannotUndo => write!(f, "cannot undo"),
+        }
+    }
+}
+
+impl Error for GtpError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            GtpError::IllegalMove(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// ```b```/```black```/```w```/```white```を手番の側として読む
+fn parse_color(s: &str) -> Result<Turn, GtpError> {
+    match s.to_ascii_lowercase().as_str() {
+        "b" | "black" => Ok(Turn::Black),
+        "w" | "white" => Ok(Turn::White),
+        _ => Err(GtpError::Syntax),
+    }
+}
+
+/// ```f5```や```pass```のような座標を着手として読む
+fn parse_vertex(s: &str) -> Result<Move, GtpError> {
+    if s.eq_ignore_ascii_case("pass") {
+        return Ok(Move::Pass);
+    }
+    s.parse().map(Move::Put).map_err(|_| GtpError::Syntax)
+}
+
+/// GTPで使う大文字の座標
+fn vertex_name(pos: Position) -> String {
+    pos.to_string().to_uppercase()
+}
+
+/// GTPのエンジンとして盤面を持ち、1行ずつの命令に答える
+///
+/// 命令は```[id] 命令 引数...```の形で、成功すれば```=[id] 結果```、
+/// 失敗すれば```?[id] メッセージ```に空行を続けて返す。
+/// パスは```BoardState```が自動で処理するので、```play```のパスは自動でパスになった側かを確かめるだけ。
+#[derive(Clone, Debug)]
+pub struct GtpEngine {
+    board: BoardState,
+    limits: SearchLimits,
+}
+
+impl Default for GtpEngine {
+    fn default() -> GtpEngine {
+        GtpEngine::new()
+    }
+}
+
+impl GtpEngine {
+    /// 8x8の初期局面と「普通」の強さで作成する
+    pub fn new() -> GtpEngine {
+        GtpEngine {
+            board: BoardState::new(4, false),
+            limits: Level::Normal.limits(),
+        }
+    }
+
+    /// 今の盤面
+    pub fn board(&self) -> &BoardState {
+        &self.board
+    }
+
+    /// 1行分の命令を処理して応答をoutに書く
+    ///
+    /// ```#```以降は注釈として読み飛ばし、空行には何も返さない。
+    /// ```quit```を受け取ったら応答を書いてから```false```を返す。
+    pub fn handle<W: Write>(&mut self, line: &str, out: &mut W) -> io::Result<bool> {
+        let line = line.split('#').next().unwrap_or("");
+        let mut tokens = line.split_whitespace().peekable();
+        let id = match tokens.peek().and_then(|t| t.parse::<u32>().ok()) {
+            Some(id) => {
+                tokens.next();
+                id.to_string()
+            }
+            None => String::new(),
+        };
+        let command = match tokens.next() {
+            Some(command) => command,
+            None => return Ok(true),
+        };
+        let args: Vec<&str> = tokens.collect();
+
+        match self.execute(command, &args) {
+            Ok(response) if response.is_empty() => writeln!(out, "={}\n", id)?,
+            Ok(response) => writeln!(out, "={} {}\n", id, response)?,
+            Err(e) => writeln!(out, "?{} {}\n", id, e)?,
+        }
+        out.flush()?;
+        Ok(command != "quit")
+    }
+
+    /// 命令を実行して応答の本文を返す
+    pub fn execute(&mut self, command: &str, args: &[&str]) -> Result<String, GtpError> {
+        match command {
+            "protocol_version" => Ok(PROTOCOL_VERSION.to_string()),
+            "name" => Ok(ENGINE_NAME.to_string()),
+            "version" => Ok(env!("CARGO_PKG_VERSION").to_string()),
+            "known_command" => {
+                let name = args.first().ok_or(GtpError::Syntax)?;
+                Ok(COMMANDS.contains(name).to_string())
+            }
+            "list_commands" => Ok(COMMANDS.join("\n")),
+            "quit" => Ok(String::new()),
+            "boardsize" => {
+                let n: usize = args
+                    .first()
+                    .and_then(|a| a.parse().ok())
+                    .ok_or(GtpError::Syntax)?;
+                if n < 4 || !n.is_multiple_of(2) {
+                    return Err(GtpError::UnacceptableSize);
+                }
+                self.board = BoardState::new(n / 2, false);
+                Ok(String::new())
+            }
+            "clear_board" => {
+                self.board = BoardState::new(self.board.get_size() / 2, false);
+                Ok(String::new())
+            }
+            "play" => match args {
+                [color, vertex] => {
+                    self.play(parse_color(color)?, parse_vertex(vertex)?)?;
+                    Ok(String::new())
+                }
+                _ => Err(GtpError::Syntax),
+            },
+            "genmove" => match args {
+                [color] => self.genmove(parse_color(color)?),
+                _ => Err(GtpError::Syntax),
+            },
+            "undo" => {
+                // 最後がパスならそのパスだけを取り消し、前の着手は残す
+                if !self.board.take_back_pass() {
+                    self.board.undo().ok_or(GtpError::CannotUndo)?;
+                }
+                Ok(String::new())
+            }
+            "showboard" => Ok(self.showboard()),
+            "final_score" => Ok(self.final_score()),
+            _ => Err(GtpError::UnknownCommand(command.to_string())),
+        }
+    }
+
+    /// colorの側がもうパスしたことになっているか（ゲームが終わった後も含む）
+    fn has_passed(&self, color: Turn) -> bool {
+        if self.board.is_game_over() {
+            return true;
+        }
+        match self.board.history().last() {
+            Some(entry) => entry.mv == Move::Pass && entry.player == color,
+            None => false,
+        }
+    }
+
+    /// colorの手番なのに置ける場所がないか（```undo```でパスを取り消した直後の局面）
+    fn must_pass(&self, color: Turn) -> bool {
+        self.board.turn() == color && self.board.legal_moves().is_empty()
+    }
+
+    /// colorの側の着手を盤面に反映する
+    fn play(&mut self, color: Turn, mv: Move) -> Result<(), GtpError> {
+        match mv {
+            Move::Pass if self.has_passed(color) => Ok(()),
+            Move::Pass if self.must_pass(color) => {
+                self.board.pass();
+                Ok(())
+            }
+            Move::Pass if self.board.turn() == color => Err(GtpError::IllegalPass),
+            Move::Pass => Err(GtpError::WrongColor),
+            Move::Put(_) if self.board.turn() != color => Err(GtpError::WrongColor),
+            Move::Put(pos) => self
+                .board
+                .try_put(pos.x, pos.y)
+                .map(|_| ())
+                .map_err(GtpError::IllegalMove),
+        }
+    }
+
+    /// colorの側の手を探索で選んで盤面に反映し、その座標を返す
+    fn genmove(&mut self, color: Turn) -> Result<String, GtpError> {
+        if self.has_passed(color) {
+            return Ok("pass".to_string());
+        }
+        if self.board.turn() != color {
+            return Err(GtpError::WrongColor);
+        }
+        if self.must_pass(color) {
+            self.board.pass();
+            return Ok("pass".to_string());
+        }
+        let pos = search(&self.board, self.limits)
+            .best_move
+            .ok_or(GtpError::IllegalPass)?;
+        self.board
+            .try_put(pos.x, pos.y)
+            .map_err(GtpError::IllegalMove)?;
+        Ok(vertex_name(pos))
+    }
+
+    /// 列名と行番号をつけた盤面と、駒の数と手番
+    fn showboard(&self) -> String {
+        let n = self.board.get_size();
+        let width = n.to_string().len();
+        let header: Vec<String> = (0..n).map(column_name).collect();
+        let mut lines = vec![format!("{:w$} {}", "", header.join(" "), w = width)];
+        for (i, row) in self.board.show_board().iter().enumerate() {
+            let cells: Vec<String> = row.iter().map(|c| c.to_string()).collect();
+            lines.push(format!("{:>w$} {}", i + 1, cells.join(" "), w = width));
+        }
+        let ((white_piece, white), (black_piece, black)) = self.board.count_pieces();
+        lines.push(format!(
+            "{}:{} {}:{} turn:{}",
+            black_piece,
+            black,
+            white_piece,
+            white,
+            self.board.which_turn()
+        ));
+        format!("\n{}", lines.join("\n"))
+    }
+
+    /// ```B+4```、```W+2```、```0```のような形の石差
+    fn final_score(&self) -> String {
+        let ((_, white), (_, black)) = self.board.count_pieces();
+        if black > white {
+            format!("B+{}", black - white)
+        } else if white > black {
+            format!("W+{}", white - black)
+        } else {
+            "0".to_string()
+        }
+    }
+}
+
+/// inputから命令を1行ずつ読んで、応答をoutに書く（```quit```か入力の終わりまで）
+pub fn run<R: BufRead, W: Write>(input: R, mut out: W) -> io::Result<()> {
+    let mut engine = GtpEngine::new();
+    for line in input.lines() {
+        if !engine.handle(&line?, &mut out)? {
+            break;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,152 @@
+use std::collections::VecDeque;
+use std::io;
+use std::time::{Duration, Instant};
+
+use crossterm::event::{poll, read, Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::{ErrorKind, Result};
+
+/// 画面の操作に使うキー
+///
+/// ```Quit```は終了するキー（Ctrl-C、Escキー、qキー）で、どの画面でも受け付ける。
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Key {
+    Up,
+    Down,
+    Left,
+    Right,
+    Enter,
+    Backspace,
+    Char(char),
+    Quit,
+}
+
+impl Key {
+    /// ターミナルのイベントから変換する（操作に使わないイベントは```None```）
+    pub fn from_event(event: &Event) -> Option<Key> {
+        let KeyEvent { code, modifiers } = match event {
+            Event::Key(key) => *key,
+            _ => return None,
+        };
+        if modifiers.contains(KeyModifiers::CONTROL) {
+            return if code == KeyCode::Char('c') {
+                Some(Key::Quit)
+            } else {
+                None
+            };
+        }
+        match code {
+            KeyCode::Esc | KeyCode::Char('q') => Some(Key::Quit),
+            KeyCode::Up => Some(Key::Up),
+            KeyCode::Down => Some(Key::Down),
+            KeyCode::Left => Some(Key::Left),
+            KeyCode::Right => Some(Key::Right),
+            KeyCode::Enter => Some(Key::Enter),
+            KeyCode::Backspace => Some(Key::Backspace),
+            KeyCode::Char(c) if !modifiers.contains(KeyModifiers::ALT) => Some(Key::Char(c)),
+            _ => None,
+        }
+    }
+}
+
+/// 画面がキー入力を読む元
+///
+/// 普段はターミナル（```TerminalInput```）から読むが、```ScriptedInput```に替えれば
+/// 決めておいたキーの並びで画面を操作できる（テスト用）。
+/// 通信相手の手を待つ間は別のスレッドから読むので、スレッドをまたいで渡せるものにする。
+pub trait InputSource: Send {
+    /// キー入力を1つ読む（操作に使わないイベントは読み飛ばす）
+    fn read_key(&mut self) -> Result<Key>;
+
+    /// 時間を空ける（その間に終了するキーが押されたらtrueを返す）
+    fn wait(&mut self, duration: Duration) -> Result<bool>;
+}
+
+/// ターミナルからキー入力を読む
+#[derive(Copy, Clone, Debug, Default)]
+pub struct TerminalInput;
+
+impl InputSource for TerminalInput {
+    fn read_key(&mut self) -> Result<Key> {
+        loop {
+            if let Some(key) = Key::from_event(&read()?) {
+                return Ok(key);
+            }
+        }
+    }
+
+    /// 待っている間に押された終了するキー以外のキーは読み捨てる
+    fn wait(&mut self, duration: Duration) -> Result<bool> {
+        let deadline = Instant::now() + duration;
+        loop {
+            let now = Instant::now();
+            if now >= deadline {
+                return Ok(false);
+            }
+            if poll(deadline - now)? && Key::from_event(&read()?) == Some(Key::Quit) {
+                return Ok(true);
+            }
+        }
+    }
+}
+
+/// 決めておいたキーの並びを順に返す（テスト用）
+///
+/// 待つときは時間を空けずにすぐ戻り、次のキーが終了するキーならそれを読んでtrueを返す
+/// （CPUや通信相手の番の途中で終了させられる）。キーを使い切った後に読もうとするとエラーになる。
+#[derive(Clone, Debug, Default)]
+pub struct ScriptedInput {
+    keys: VecDeque<Key>,
+}
+
+impl ScriptedInput {
+    /// キーの並びから作る
+    pub fn new<I: IntoIterator<Item = Key>>(keys: I) -> ScriptedInput {
+        ScriptedInput {
+            keys: keys.into_iter().collect(),
+        }
+    }
+
+    /// ターミナルのイベントの並びから作る（操作に使わないイベントは飛ばす）
+    pub fn from_events<'a, I: IntoIterator<Item = &'a Event>>(events: I) -> ScriptedInput {
+        ScriptedInput::new(events.into_iter().filter_map(Key::from_event))
+    }
+
+    /// 文字列の各文字を```Key::Char```にして後ろに足す（```'\n'```はEnterキー）
+    pub fn type_text(&mut self, text: &str) -> &mut ScriptedInput {
+        self.keys.extend(text.chars().map(|c| match c {
+            '\n' => Key::Enter,
+            c => Key::Char(c),
+        }));
+        self
+    }
+
+    /// キーを後ろに足す
+    pub fn push(&mut self, key: Key) -> &mut ScriptedInput {
+        self.keys.push_back(key);
+        self
+    }
+
+    /// まだ読まれていないキーの数
+    pub fn remaining(&self) -> usize {
+        self.keys.len()
+    }
+}
+
+impl InputSource for ScriptedInput {
+    fn read_key(&mut self) -> Result<Key> {
+        self.keys.pop_front().ok_or_else(|| {
+            ErrorKind::IoError(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "用意したキー入力を使い切りました",
+            ))
+        })
+    }
+
+    fn wait(&mut self, _duration: Duration) -> Result<bool> {
+        if self.keys.front() == Some(&Key::Quit) {
+            self.keys.pop_front();
+            return Ok(true);
+        }
+        Ok(false)
+    }
+}
@@ -0,0 +1,30 @@
+//! 簡易的なリバーシ（いわゆるオセロ）のルールエンジン。
+//!
+//! 盤面の状態と着手の処理は```BoardState```が受け持つ。
+//! ターミナルで遊ぶための画面は```screen```にあり、画面はすべて```render```の```Renderer```を通して描き、
+//! キー入力は```input```の```InputSource```から読む（テストではターミナルの代わりにメモリ上の画面と
+//! 用意したキー入力で動かせる）。バイナリ側（```main.rs```）は
+//! コマンドライン引数に応じてそれや他のモードを呼び出すだけという位置付け。
+
+pub mod bitboard;
+pub mod boardstate;
+pub mod endgame;
+pub mod ggf;
+pub mod gtp;
+pub mod input;
+pub mod moves;
+pub mod nboard;
+pub mod netplay;
+pub mod notation;
+pub mod player;
+pub mod render;
+pub mod savefile;
+pub mod screen;
+pub mod search;
+pub mod spectate;
+pub mod tournament;
+pub mod widebitboard;
+pub mod wthor;
+
+pub use boardstate::{Backend, BoardState, HistoryEntry, ParseBoardError, Turn};
+pub use moves::{Move, MoveError, MoveOutcome, Position};
@@ -1,745 +1,480 @@
-use crossterm::cursor::{EnableBlinking, Hide, MoveTo, Show};
-use crossterm::event::{read, Event, KeyCode};
-use crossterm::style::{Colorize, Print, ResetColor, Styler};
-use crossterm::terminal::{
-    disable_raw_mode, enable_raw_mode, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen,
-};
+use crossterm::cursor::MoveTo;
+use crossterm::event::read;
+use crossterm::execute;
 use crossterm::Result;
-use crossterm::{execute, queue};
-
-use std::io::{stdout, Write};
-use std::thread::sleep;
-use std::time::Duration;
-
-use rand::seq::SliceRandom;
-use rand::thread_rng;
 
-pub mod boardstate;
-use boardstate::BoardState;
-
-/// 整数の入力が不正である旨のメッセージ
-fn err_not_int() {
-    println!("半角数字で整数を入力してください．");
-}
-
-/// 入力が不適切な旨のメッセージ
-fn err_input() {
-    println!("入力が不適切です．");
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, stdout, BufReader};
+use std::net::TcpListener;
+use std::process::exit;
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Instant;
+
+use rand::random;
+
+use simple_reversi_2::input::Key;
+use simple_reversi_2::netplay::{accept_game, join_game, NetError, DEFAULT_PORT, MAX_SIZE};
+use simple_reversi_2::player::PlayerKind;
+use simple_reversi_2::render::{CrosstermRenderer, Renderer, Style};
+use simple_reversi_2::screen::{
+    self, draw_header, level_label, preview_board, preview_turn, restore_terminal, show_result,
+    TerminalGuard,
+};
+use simple_reversi_2::search::Level;
+use simple_reversi_2::spectate::{SpectatorClient, SpectatorServer};
+use simple_reversi_2::tournament::{cpu_kind, MoveTimes, Tournament};
+use simple_reversi_2::wthor::{WthorError, WthorReader};
+use simple_reversi_2::{gtp, nboard};
+use simple_reversi_2::{BoardState, Turn};
+
+/// コマンドライン引数の内容
+///
+/// perftが指定されたときはゲームをせずにperftの結果を表示して終わる。
+/// sizeはperftで使う盤面のサイズ。
+/// seedはCPUの乱数のシードで，同じシードなら同じ対局を再現できる（省略するとランダムに決める）。
+/// wthorが指定されたときはゲームをせずにWTHORのファイルの集計結果を表示して終わる。
+/// nboardがtrueのときは画面を使わず，標準入出力でNBoardのエンジンとして動く。
+/// gtpがtrueのときは同じく標準入出力でGTPの命令を受け付ける。
+/// host/joinは通信対戦で待ち受けるアドレスと接続先のアドレス（待ち受けた側が黒，サイズはsizeで指定）。
+/// spectateは対局を観戦者に配信するアドレス，watchは観戦する配信のアドレス。
+/// tournamentが指定されたときは画面を使わずにplayersの2人のCPUをその数だけ対局させて成績を表示する。
+#[derive(Default)]
+struct Args {
+    perft: Option<usize>,
+    size: Option<usize>,
+    seed: Option<u64>,
+    wthor: Option<String>,
+    nboard: bool,
+    gtp: bool,
+    host: Option<String>,
+    join: Option<String>,
+    spectate: Option<String>,
+    watch: Option<String>,
+    tournament: Option<usize>,
+    players: Option<[PlayerKind; 2]>,
 }
 
-/// カーソル位置は青の太字にするように盤面を表示させるキュー
-fn preview_board(bs: &BoardState, cursor_x: usize, cursor_y: usize, row_now: u16) {
-    let v = bs.show_board();
-    let n = bs.get_size();
-
-    for i in 0..n {
-        queue!(
-            stdout(),
-            MoveTo(0, row_now + i as u16),
-            Clear(ClearType::CurrentLine),
-        );
-        for j in 0..n {
-            if i == cursor_x && j == cursor_y {
-                queue!(
-                    stdout(),
-                    MoveTo(2 * j as u16, row_now + i as u16),
-                    Print(" "),
-                    Print(v[i][j].bold().blue().on_yellow()),
-                );
-            } else {
-                queue!(
-                    stdout(),
-                    MoveTo(2 * j as u16, row_now + i as u16),
-                    Print(" "),
-                    Print(v[i][j])
-                );
+/// コマンドライン引数を読む
+fn parse_args(args: &[String]) -> std::result::Result<Args, String> {
+    let mut parsed = Args::default();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--perft" => parsed.perft = Some(parse_value(arg, iter.next())?),
+            "--size" => {
+                let n: usize = parse_value(arg, iter.next())?;
+                if n < 4 || !n.is_multiple_of(2) {
+                    return Err("盤面のサイズは4以上の偶数で指定してください．".to_string());
+                }
+                parsed.size = Some(n);
+            }
+            "--seed" => parsed.seed = Some(parse_value(arg, iter.next())?),
+            "--wthor" => parsed.wthor = Some(parse_value(arg, iter.next())?),
+            "--nboard" => parsed.nboard = true,
+            "--gtp" => parsed.gtp = true,
+            "--host" => parsed.host = Some(parse_value(arg, iter.next())?),
+            "--join" => parsed.join = Some(parse_value(arg, iter.next())?),
+            "--spectate" => parsed.spectate = Some(parse_value(arg, iter.next())?),
+            "--watch" => parsed.watch = Some(parse_value(arg, iter.next())?),
+            "--tournament" => parsed.tournament = Some(parse_value(arg, iter.next())?),
+            "--players" => {
+                let names: String = parse_value(arg, iter.next())?;
+                let kinds: Vec<PlayerKind> = names.split(',').filter_map(cpu_kind).collect();
+                match kinds[..] {
+                    [a, b] if names.split(',').count() == 2 => parsed.players = Some([a, b]),
+                    _ => {
+                        return Err(
+                            "--playersの後にはrandom，weak，normal，strongから2つをweak,normalのようにカンマで区切って指定してください．"
+                                .to_string(),
+                        )
+                    }
+                }
             }
+            _ => return Err(format!("不明なオプションです：{}", arg)),
         }
     }
+    Ok(parsed)
 }
 
-/// 置けるマス目に+印をつけ，カーソル位置は青の太字にするように盤面を表示させるキュー
-fn preview_board_with_help(bs: &BoardState, cursor_x: usize, cursor_y: usize, row_now: u16) {
-    let v = bs.show_board();
-    let cnt = bs.cnt_reversable();
-    let n = bs.get_size();
-    for i in 0..n {
-        queue!(
-            stdout(),
-            MoveTo(0, row_now + i as u16),
-            Clear(ClearType::CurrentLine),
+/// オプションの値を読む
+fn parse_value<T: std::str::FromStr>(
+    option: &str,
+    value: Option<&String>,
+) -> std::result::Result<T, String> {
+    value
+        .and_then(|v| v.parse().ok())
+        .ok_or_else(|| format!("{}の後に正しい値を指定してください．", option))
+}
+
+/// 深さ1からdepthまでのperftの結果を表示する
+fn run_perft(size: usize, depth: usize) {
+    let bs = BoardState::new(size / 2, false);
+    println!("盤面：{0} x {0}", size);
+    for d in 1..=depth {
+        let start = Instant::now();
+        let nodes = bs.perft(d);
+        println!(
+            "perft({}) = {} （{:.3}秒）",
+            d,
+            nodes,
+            start.elapsed().as_secs_f64()
         );
-        for j in 0..n {
-            if i == cursor_x && j == cursor_y {
-                queue!(
-                    stdout(),
-                    MoveTo(2 * j as u16, row_now + i as u16),
-                    Print(" "),
-                    Print(
-                        (if cnt[i][j] > 0 { '+' } else { v[i][j] })
-                            .bold()
-                            .blue()
-                            .on_yellow()
-                    ),
-                );
-            } else {
-                queue!(
-                    stdout(),
-                    MoveTo(2 * j as u16, row_now + i as u16),
-                    Print(" "),
-                    Print(if cnt[i][j] > 0 { '+' } else { v[i][j] })
-                );
-            }
-        }
     }
 }
 
-/// どちらのターンかを表示する
-fn preview_turn(bs: &BoardState) -> String {
-    format!("{}のターン．", bs.which_turn())
+/// 連続対局の成績に表示する指し手の名前（何人目かと強さ）
+fn tournament_name(k: usize, kind: PlayerKind) -> String {
+    format!("{}人目（{}）", k + 1, level_label(kind))
 }
 
-/// 結果を表示する
-fn show_result(bs: &BoardState) -> String {
-    let ((c1, s1), (c2, s2)) = bs.count_pieces();
-    if s1 > s2 {
-        format!("{0}が{1}個，{2}が{3}個で{0}の勝ち！", c1, s1, c2, s2)
-    } else if s1 < s2 {
-        format!("{0}が{1}個，{2}が{3}個で{2}の勝ち！", c1, s1, c2, s2)
-    } else {
-        format!("{0}が{1}個，{2}が{3}個で引き分け！", c1, s1, c2, s2)
-    }
+/// 1手あたりの時間を表示する
+fn print_move_times(name: &str, times: &MoveTimes) {
+    println!(
+        "{}の1手あたりの時間：平均 {:.3}ミリ秒，最長 {:.3}ミリ秒（{}手）",
+        name,
+        times.average().as_secs_f64() * 1000.0,
+        times.max_time.as_secs_f64() * 1000.0,
+        times.moves
+    );
 }
 
-fn main() -> Result<()> {
-    // Alternate Screen に入り、画面をクリアし、カーソルを非表示にし、Simple Reversi と表示
-    execute!(
-        stdout(),
-        EnterAlternateScreen,
-        Clear(ClearType::All),
-        MoveTo(0, 0),
-        Hide,
-        Print(" ===== Simple Reversi ===== ".to_string().red().bold()),
-        MoveTo(0, 1)
-    )?;
-
-    // ゲーム開始までは標準入力から行いたいのでRAWモードにはまだ入らない
-
-    // 盤面サイズの入力・決定
-    let size: usize;
-    loop {
-        println!("盤面のサイズを4以上の偶数で半角数字で入力してください．Enterキーで確定します．");
-        let mut size_string = String::new();
-        std::io::stdin().read_line(&mut size_string).ok();
-        if let Ok(n) = size_string.trim().parse::<usize>() {
-            if n >= 4 && n % 2 == 0 {
-                size = n;
-                break;
-            } else {
-                err_input();
-            }
-        } else {
-            err_not_int();
+/// 2人のCPUを画面なしで続けて対局させ，成績を表示する
+///
+/// 進み具合は標準エラー出力に表示する．
+fn run_tournament(tournament: &Tournament) {
+    let names = [
+        tournament_name(0, tournament.players[0]),
+        tournament_name(1, tournament.players[1]),
+    ];
+    println!(
+        "盤面：{0} x {0}　{1}局　{2} 対 {3}（シード：{4}）",
+        tournament.size, tournament.games, names[0], names[1], tournament.seed
+    );
+    let result = tournament.run(|game, _| {
+        eprint!("\r{}/{}局", game + 1, tournament.games);
+    });
+    eprintln!();
+    let stats = match result {
+        Ok(stats) => stats,
+        Err(e) => {
+            eprintln!("{}", e);
+            exit(1);
         }
+    };
+
+    println!(
+        "{}の成績：{}勝{}敗{}分（勝率 {:.1}%，引き分けは0.5勝）",
+        names[0],
+        stats.wins,
+        stats.losses,
+        stats.draws,
+        stats.score_rate() * 100.0
+    );
+    println!(
+        "平均の石差：{:+.2}（{}から見た値）",
+        stats.average_disc_diff(),
+        names[0]
+    );
+    for (name, times) in names.iter().zip(stats.times.iter()) {
+        print_move_times(name, times);
     }
+}
 
-    // ここからRAWモードに入る
-    enable_raw_mode()?;
-
-    // 盤面サイズを常時表示
-    execute!(
-        stdout(),
-        Clear(ClearType::All),
-        MoveTo(0, 0),
-        Print(" ===== Simple Reversi ===== ".to_string().red().bold()),
-        MoveTo(0, 1),
-        Print(format!("盤面：{0} x {0}", size).to_string()),
-    )?;
-
-    // CPUとやるかどうかの入力・決定
-    let mut cpu_flag = false;
-    let mut cpu_only_flag = false;
-
-    let mut item_num: usize = 0;
-    let mut enter = false;
-    loop {
-        // 常時表示
-        execute!(
-            stdout(),
-            Clear(ClearType::All),
-            MoveTo(0, 0),
-            Print(" ===== Simple Reversi ===== ".to_string().red().bold()),
-            MoveTo(0, 1),
-            Print(format!("盤面：{0} x {0}", size).to_string()),
-            MoveTo(0, 2),
-            Print("モードを選択してください．↑↓キーで選択，Enterキーで決定．"),
-        )?;
-        // 選択肢を表示
-        if item_num == 0 {
-            execute!(stdout(), MoveTo(0, 3), Print("CPU対戦モード".blue().bold()),)?;
-        } else {
-            execute!(stdout(), MoveTo(0, 3), Print("CPU対戦モード"),)?;
-        }
-        if item_num == 1 {
-            execute!(stdout(), MoveTo(0, 4), Print("観戦モード".blue().bold()),)?;
-        } else {
-            execute!(stdout(), MoveTo(0, 4), Print("観戦モード"),)?;
-        }
-        if item_num == 2 {
-            execute!(stdout(), MoveTo(0, 5), Print("1人2役モード".blue().bold()),)?;
-        } else {
-            execute!(stdout(), MoveTo(0, 5), Print("1人2役モード"),)?;
-        }
-        // キー入力読み込み
-        loop {
-            let event = read()?;
+/// 序盤の集計に使う手数
+const OPENING_MOVES: usize = 4;
 
-            if event == Event::Key(KeyCode::Up.into()) {
-                item_num = if item_num > 0 { item_num - 1 } else { item_num };
-                break;
-            }
+/// 集計結果に表示する序盤の数
+const TOP_OPENINGS: usize = 10;
 
-            if event == Event::Key(KeyCode::Down.into()) {
-                item_num = if item_num < 2 { item_num + 1 } else { item_num };
-                break;
-            }
+/// 割合を百分率で表示する
+fn percent(count: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        100.0 * count as f64 / total as f64
+    }
+}
 
-            if event == Event::Key(KeyCode::Enter.into()) {
-                enter = true;
-                break;
+/// WTHORのファイルの対局を集計して表示する
+///
+/// 勝敗の内訳，黒の石数の平均，よく打たれた序盤を表示する．
+/// 打てない手がある対局は読み飛ばして数だけ表示する．
+fn run_wthor(path: &str) -> std::result::Result<(), WthorError> {
+    let reader = WthorReader::new(BufReader::new(File::open(path)?))?;
+    let header = reader.header();
+
+    let mut games: usize = 0;
+    let mut skipped: usize = 0;
+    let (mut black_wins, mut white_wins, mut draws) = (0, 0, 0);
+    let mut black_discs: usize = 0;
+    // 序盤の棋譜ごとの（対局数，黒の勝ち数）
+    let mut openings: HashMap<String, (usize, usize)> = HashMap::new();
+    for game in reader {
+        let game = match game {
+            Ok(game) => game,
+            Err(WthorError::Io(e)) => return Err(WthorError::Io(e)),
+            Err(e) => {
+                eprintln!("{}", e);
+                skipped += 1;
+                continue;
             }
-
-            // windowのサイズが変わったときは再描画→点滅の元なのでやはり削除
-            // if let Event::Resize(_,_) = event {
-            //    break;
-            // }
+        };
+        games += 1;
+        black_discs += game.black_score as usize;
+        let black_won = game.black_score > 32;
+        if black_won {
+            black_wins += 1;
+        } else if game.black_score < 32 {
+            white_wins += 1;
+        } else {
+            draws += 1;
         }
-        if enter {
-            break;
+        if game.moves.len() >= OPENING_MOVES {
+            let opening: String = game.moves[..OPENING_MOVES]
+                .iter()
+                .map(|pos| pos.to_string())
+                .collect();
+            let entry = openings.entry(opening).or_insert((0, 0));
+            entry.0 += 1;
+            entry.1 += black_won as usize;
         }
     }
 
-    if item_num == 0 {
-        cpu_flag = true;
-    } else if item_num == 1 {
-        cpu_only_flag = true;
+    let (y, m, d) = header.created;
+    println!("作成日：{}年{}月{}日", y, m, d);
+    println!("対局の年：{}", header.year);
+    println!("対局数：{}", games);
+    if skipped > 0 {
+        println!("読み飛ばした対局：{}", skipped);
     }
-
-    // 2行目以降を消す
-    for i in (2..=crossterm::cursor::position().unwrap().1).rev() {
-        execute!(stdout(), MoveTo(0, i), Clear(ClearType::CurrentLine),)?;
+    println!(
+        "黒の勝ち：{}（{:.1}%）",
+        black_wins,
+        percent(black_wins, games)
+    );
+    println!(
+        "白の勝ち：{}（{:.1}%）",
+        white_wins,
+        percent(white_wins, games)
+    );
+    println!("引き分け：{}（{:.1}%）", draws, percent(draws, games));
+    if games > 0 {
+        println!("黒の石数の平均：{:.2}", black_discs as f64 / games as f64);
     }
 
-    // モードを常時表示
-    execute!(
-        stdout(),
-        MoveTo(0, 2),
-        Print(
-            if cpu_flag {
-                "CPU対戦モード"
-            } else if cpu_only_flag {
-                "観戦モード"
-            } else {
-                "1人2役モード"
-            }
-            .to_string()
-        ),
-    )?;
-
-    let mut i_am_white = false;
-
-    if cpu_flag {
-        // どちらの番から始めるかの入力・決定
-        let mut enter = false;
-        loop {
-            // 常時表示
-            execute!(
-                stdout(),
-                Clear(ClearType::All),
-                MoveTo(0, 0),
-                Print(" ===== Simple Reversi ===== ".to_string().red().bold()),
-                MoveTo(0, 1),
-                Print(format!("盤面：{0} x {0}", size).to_string()),
-                MoveTo(0, 2),
-                Print(
-                    if cpu_flag {
-                        "CPU対戦モード"
-                    } else if cpu_only_flag {
-                        "観戦モード"
-                    } else {
-                        "1人2役モード"
-                    }
-                    .to_string()
-                ),
-                MoveTo(0, 3),
-                Print(format!(
-                    "{0}と{1}，どちらから始めますか？ {0}が先攻です．←→キーで選択，Enterキーで決定．",
-                    BoardState::black_piece(),
-                    BoardState::white_piece()
-                )),
-            )?;
-            if !i_am_white {
-                execute!(
-                    stdout(),
-                    MoveTo(3, 4),
-                    Print(format!("{}", BoardState::black_piece()).blue().bold()),
-                )?;
-            } else {
-                execute!(
-                    stdout(),
-                    MoveTo(3, 4),
-                    Print(format!("{}", BoardState::black_piece())),
-                )?;
-            }
-            if i_am_white {
-                execute!(
-                    stdout(),
-                    MoveTo(6, 4),
-                    Print(format!("{}", BoardState::white_piece()).blue().bold()),
-                )?;
-            } else {
-                execute!(
-                    stdout(),
-                    MoveTo(6, 4),
-                    Print(format!("{}", BoardState::white_piece())),
-                )?;
-            }
-            // キー入力読み込み
-            loop {
-                let event = read()?;
-
-                if event == Event::Key(KeyCode::Left.into()) {
-                    if i_am_white {
-                        i_am_white = false;
-                    }
-                    break;
-                }
+    let mut openings: Vec<(String, (usize, usize))> = openings.into_iter().collect();
+    openings.sort_by(|a, b| (b.1).0.cmp(&(a.1).0).then_with(|| a.0.cmp(&b.0)));
+    println!("よく打たれた序盤（最初の{}手）：", OPENING_MOVES);
+    for (opening, (count, wins)) in openings.iter().take(TOP_OPENINGS) {
+        println!(
+            "  {}  {}局（黒の勝率 {:.1}%）",
+            opening,
+            count,
+            percent(*wins, *count)
+        );
+    }
+    Ok(())
+}
 
-                if event == Event::Key(KeyCode::Right.into()) {
-                    if !i_am_white {
-                        i_am_white = true;
-                    }
-                    break;
-                }
+fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args = match parse_args(&args) {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("{}", message);
+            exit(2);
+        }
+    };
 
-                if event == Event::Key(KeyCode::Enter.into()) {
-                    enter = true;
-                    break;
-                }
+    if let Some(depth) = args.perft {
+        run_perft(args.size.unwrap_or(8), depth);
+        return Ok(());
+    }
 
-                // windowのサイズが変わったときは再描画→点滅の元なのでやはり削除
-                // if let Event::Resize(_,_) = event {
-                //     break;
-                // }
-            }
-            if enter {
-                break;
-            }
+    if let Some(path) = args.wthor {
+        if let Err(e) = run_wthor(&path) {
+            eprintln!("{}：{}", path, e);
+            exit(1);
         }
+        return Ok(());
     }
 
-    // 盤面作成
-    let mut bs = BoardState::new(size / 2, false);
-
-    // ヘルプ（+印）を表示するかどうか
-    let mut with_help_or_not = false;
-
-    // 「そこには置けません．を表示するかどうか」
-    let mut not_puttable_message = false;
-
-    // カーソル位置
-    // 観戦モードのときはカーソルを出さないようにする工夫
-    let mut cursor_x: usize = 0;
-    let mut cursor_y: usize = if cpu_only_flag { size } else { 0 };
-
-    // ゲーム実行
-    loop {
-        // 一旦画面をクリアし、タイトルその他諸々を表示
-        execute!(
-            stdout(),
-            EnterAlternateScreen,
-            Clear(ClearType::All),
-            MoveTo(0, 0),
-            Print(" ===== Simple Reversi ===== ".to_string().red().bold()),
-            MoveTo(0, 1),
-            Print(format!("盤面：{0} x {0}", size).to_string()),
-            MoveTo(0, 2),
-            Print(
-                if cpu_flag {
-                    "CPU対戦モード"
-                } else if cpu_only_flag {
-                    "観戦モード"
-                } else {
-                    "1人2役モード"
-                }
-                .to_string()
-            ),
-            MoveTo(0, 3),
-            Print(
-                preview_turn(&bs)
-                    + if (cpu_flag
-                        && (i_am_white || bs.is_it_white_turn())
-                        && !(i_am_white && bs.is_it_white_turn()))
-                        || cpu_only_flag
-                    {
-                        ""
-                    } else {
-                        "↑↓←→キーで選択，Enterキーで決定．"
-                    }
-            ),
-        )?;
-
-        // 「そこには置けません」メッセージの表示
-        if not_puttable_message {
-            execute!(
-                stdout(),
-                MoveTo(0, 6 + size as u16),
-                Clear(ClearType::CurrentLine),
-                Print("そこには置けません".red().bold()),
-            )?;
-        }
-        not_puttable_message = false;
-
-        // 盤面の表示
-        if with_help_or_not {
-            preview_board_with_help(
-                &bs,
-                cursor_x,
-                if (cpu_flag
-                    && (i_am_white || bs.is_it_white_turn())
-                    && !(i_am_white && bs.is_it_white_turn()))
-                    || cpu_only_flag
-                {
-                    size
-                } else {
-                    cursor_y
-                },
-                4,
-            );
-        } else {
-            preview_board(
-                &bs,
-                cursor_x,
-                if (cpu_flag
-                    && (i_am_white || bs.is_it_white_turn())
-                    && !(i_am_white && bs.is_it_white_turn()))
-                    || cpu_only_flag
-                {
-                    size
-                } else {
-                    cursor_y
-                },
-                4,
-            );
-        }
+    if args.nboard {
+        // GUIとのやり取りに使うので，rawモードや代替スクリーンには入らない
+        let stdin = io::stdin();
+        nboard::run(stdin.lock(), stdout())?;
+        return Ok(());
+    }
 
-        // 盤面表示キューの内容を実行
-        stdout().flush()?;
-
-        // CPUの番の場合
-        if (cpu_flag
-            && (i_am_white || bs.is_it_white_turn())
-            && !(i_am_white && bs.is_it_white_turn()))
-            || cpu_only_flag
-        {
-            // 乱数発生用
-            let mut rng = thread_rng();
-
-            // 時間を空けつつメッセージを表示
-            if cpu_flag {
-                sleep(Duration::from_millis(250));
-            }
-            execute!(
-                stdout(),
-                MoveTo(0, 5 + size as u16),
-                Print("CPU操作中...".bold()),
-            )?;
-            sleep(Duration::from_millis(if cpu_only_flag { 500 } else { 750 }));
-
-            // 置けるマス目を重み付けしつつVecで管理
-            let mut options: Vec<(usize, usize)> = Vec::new();
-            let mut options_corners: Vec<(usize, usize)> = Vec::new();
-            let vec = &bs.cnt_reversable();
-            let n = bs.get_size();
-            for i in 0..n {
-                for j in 0..n {
-                    if vec[i][j] > 0 {
-                        for _ in 0..vec[i][j] {
-                            options.push((i, j));
-                        }
-                        if (i == 0 || i == n - 1) && (j == 0 || j == n - 1) {
-                            options_corners.push((i, j));
-                        }
-                    }
-                }
-            }
+    if args.gtp {
+        let stdin = io::stdin();
+        gtp::run(stdin.lock(), stdout())?;
+        return Ok(());
+    }
 
-            // ランダムに選ぶ
-            let &(i, j) = if options_corners.is_empty() {
-                options
-            } else {
-                options_corners
-            }
-            .choose(&mut rng)
-            .unwrap();
+    if let Some(games) = args.tournament {
+        let tournament = Tournament {
+            size: args.size.unwrap_or(8),
+            games,
+            players: args
+                .players
+                .unwrap_or([PlayerKind::Random, PlayerKind::Search(Level::Normal)]),
+            seed: args.seed.unwrap_or_else(random),
+        };
+        run_tournament(&tournament);
+        return Ok(());
+    }
 
-            // マス目更新
-            let can_continue = bs.put(i, j);
+    if let Some(addr) = args.watch {
+        return run_watch(&network_addr(&addr, "127.0.0.1"));
+    }
 
-            // 続行できないときはループを抜けてゲームを終了
-            if !can_continue {
-                break;
+    // 観戦者への配信は対局の準備より先に始めておく
+    let spectators = match args.spectate {
+        Some(addr) => {
+            let addr = network_addr(&addr, "0.0.0.0");
+            match SpectatorServer::bind(&addr) {
+                Ok(server) => Some(server),
+                Err(e) => {
+                    eprintln!("{}で配信を始められませんでした：{}", addr, e);
+                    exit(1);
+                }
             }
-            continue;
-        }
-
-        // 以下、自分の番の場合
-
-        // 操作方法の表示
-        if cursor_x == size {
-            execute!(
-                stdout(),
-                MoveTo(0, 4 + size as u16),
-                Clear(ClearType::CurrentLine),
-                Print("駒が置ける場所のヒントを見る".blue().bold()),
-            )?;
-        } else {
-            execute!(
-                stdout(),
-                MoveTo(0, 4 + size as u16),
-                Clear(ClearType::CurrentLine),
-                Print("駒が置ける場所のヒントを見る"),
-            )?;
         }
-        if cursor_x == size + 1 {
-            execute!(
-                stdout(),
-                MoveTo(0, 5 + size as u16),
-                Clear(ClearType::CurrentLine),
-                Print("ゲームを終わって結果を見る".blue().bold()),
-            )?;
-        } else {
-            execute!(
-                stdout(),
-                MoveTo(0, 5 + size as u16),
-                Clear(ClearType::CurrentLine),
-                Print("ゲームを終わって結果を見る"),
-            )?;
+        None => None,
+    };
+
+    let seed = args.seed.unwrap_or_else(random);
+    if let Some(addr) = args.host {
+        let size = args.size.unwrap_or(8);
+        if size > MAX_SIZE {
+            eprintln!(
+                "通信対戦の盤面のサイズは{}以下で指定してください．",
+                MAX_SIZE
+            );
+            exit(1);
         }
-
-        // カーソル移動操作ならtrueを返しloopを再び回す
-        let mut move_cursor: bool = false;
-
-        // キー入力読み込み
-        loop {
-            let event = read()?;
-
-            if event == Event::Key(KeyCode::Up.into()) {
-                cursor_x = if cursor_x > 0 { cursor_x - 1 } else { cursor_x };
-                move_cursor = true;
-                break;
-            }
-
-            if event == Event::Key(KeyCode::Down.into()) {
-                cursor_x = if cursor_x <= size {
-                    cursor_x + 1
-                } else {
-                    cursor_x
-                };
-                move_cursor = true;
-                break;
+        let addr = network_addr(&addr, "0.0.0.0");
+        let game = TcpListener::bind(&addr)
+            .map_err(NetError::from)
+            .and_then(|listener| {
+                println!("{}で相手の接続を待っています．", addr);
+                accept_game(&listener, size, Turn::Black)
+            });
+        return match game {
+            Ok(game) => screen::run(seed, Some(game), spectators),
+            Err(e) => {
+                eprintln!("{}", e);
+                exit(1);
             }
-
-            if event == Event::Key(KeyCode::Left.into()) {
-                cursor_y = if cursor_y > 0 { cursor_y - 1 } else { cursor_y };
-                move_cursor = true;
-                break;
-            }
-
-            if event == Event::Key(KeyCode::Right.into()) {
-                cursor_y = if cursor_y < size - 1 {
-                    cursor_y + 1
-                } else {
-                    cursor_y
-                };
-                move_cursor = true;
-                break;
+        };
+    }
+    if let Some(addr) = args.join {
+        let addr = network_addr(&addr, "127.0.0.1");
+        println!("{}に接続しています．", addr);
+        return match join_game(&addr) {
+            Ok(game) => screen::run(seed, Some(game), spectators),
+            Err(e) => {
+                eprintln!("{}", e);
+                exit(1);
             }
+        };
+    }
 
-            // windowのサイズが変わったときは再描画→点滅の元なのでやはり削除
-            // if let Event::Resize(_,_) = event {
-            //     move_cursor = true;
-            //     break;
-            // }
+    screen::run(seed, None, spectators)
+}
 
-            if event == Event::Key(KeyCode::Enter.into()) {
-                break;
-            }
-        }
+/// 通信対戦のアドレスを補う
+///
+/// ポート番号だけならdefault_hostを，ホスト名だけならポート番号の既定値を補う．
+fn network_addr(addr: &str, default_host: &str) -> String {
+    if addr.parse::<u16>().is_ok() {
+        format!("{}:{}", default_host, addr)
+    } else if addr.contains(':') {
+        addr.to_string()
+    } else {
+        format!("{}:{}", addr, DEFAULT_PORT)
+    }
+}
 
-        // カーソルを動かしただけのときはloopを回して再描画
-        if move_cursor {
-            continue;
+/// 配信されている対局を観戦する
+///
+/// 局面が届くたびに画面を描き直す．終わったあとも盤面が残るように代替スクリーンは使わない．
+/// 局面を待つ間は別のスレッドで終了するキーを見張り，押されたらその場で終わる．
+fn run_watch(addr: &str) -> Result<()> {
+    let client = match SpectatorClient::connect(addr) {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("{}に接続できませんでした：{}", addr, e);
+            exit(1);
         }
-
-        // 終了処理
-        if cursor_x == size + 1 {
-            let mut yes = true;
-            let mut enter = false;
-            loop {
-                // 常時表示
-                execute!(
-                    stdout(),
-                    EnterAlternateScreen,
-                    Clear(ClearType::All),
-                    MoveTo(0, 0),
-                    Print(" ===== Simple Reversi ===== ".to_string().red().bold()),
-                    MoveTo(0, 1),
-                    Print(format!("盤面：{0} x {0}", size).to_string()),
-                    MoveTo(0, 2),
-                    Print(
-                        if cpu_flag {
-                            "CPU対戦モード"
-                        } else if cpu_only_flag {
-                            "観戦モード"
-                        } else {
-                            "1人2役モード"
-                        }
-                        .to_string()
-                    ),
-                    MoveTo(0, 5),
-                    Print("本当に終了しますか？".bold()),
-                )?;
-                if yes {
-                    execute!(
-                        stdout(),
-                        MoveTo(2, 7),
-                        Clear(ClearType::CurrentLine),
-                        Print("はい".blue().bold()),
-                    )?;
-                    execute!(stdout(), MoveTo(10, 7), Print("いいえ"),)?;
-                } else {
-                    execute!(
-                        stdout(),
-                        MoveTo(2, 7),
-                        Clear(ClearType::CurrentLine),
-                        Print("はい"),
-                    )?;
-                    execute!(stdout(), MoveTo(10, 7), Print("いいえ".blue().bold()),)?;
-                }
-                // キー入力読み込み
-                loop {
-                    let event = read()?;
-
-                    if event == Event::Key(KeyCode::Left.into()) {
-                        if !yes {
-                            yes = true;
-                        }
-                        break;
-                    }
-
-                    if event == Event::Key(KeyCode::Right.into()) {
-                        if yes {
-                            yes = false;
-                        }
-                        break;
-                    }
-
-                    if event == Event::Key(KeyCode::Enter.into()) {
-                        enter = true;
-                        break;
-                    }
-
-                    // windowのサイズが変わったときは再描画→点滅の元なのでやはり削除
-                    // if let Event::Resize(_,_) = event {
-                    //     break;
-                    // }
-                }
-                if enter {
-                    break;
+    };
+    println!("{}に接続しました．局面が届くのを待っています．", addr);
+    let terminal = TerminalGuard::enter(false)?;
+
+    // 盤面を描いたときは，メッセージをその下に出す（0ならまだ描いていない）
+    let bottom = Arc::new(AtomicU16::new(0));
+    let watching = Arc::clone(&bottom);
+    thread::spawn(move || loop {
+        match read() {
+            Ok(event) if Key::from_event(&event) == Some(Key::Quit) => {
+                let row = watching.load(Ordering::SeqCst);
+                if row > 0 {
+                    execute!(stdout(), MoveTo(0, row)).ok();
                 }
+                restore_terminal(false);
+                exit(0);
             }
-            if yes {
+            Ok(_) => {}
+            Err(_) => return,
+        }
+    });
+
+    let mut out = CrosstermRenderer::new(stdout());
+    let mut error = None;
+    for update in client {
+        let update = match update {
+            Ok(update) => update,
+            Err(e) => {
+                error = Some(e);
                 break;
-            } else {
-                continue;
             }
-        }
-
-        // ヘルプ表示処理
-        if cursor_x == size {
-            with_help_or_not = true;
-            continue;
-        }
-        with_help_or_not = false;
-
-        // 置けるマス目かどうか判定
-        let v = bs.cnt_reversable();
-        if v[cursor_x][cursor_y] == 0 {
-            not_puttable_message = true;
-            continue;
-        }
-
-        // マス目更新
-        let can_continue = bs.put(cursor_x, cursor_y);
-
-        // 続行できないときはループを抜けてゲームを終了
-        if !can_continue {
-            break;
-        }
+        };
+        let bs = &update.board;
+        let size = bs.get_size();
+        let last_move = match update.last_move {
+            Some(pos) => format!("直前の手：{}", pos),
+            None => "直前の手：なし".to_string(),
+        };
+        let note = format!("　{}を観戦中（Escキーで終了）", addr);
+        draw_header(&mut out, Some(size), None, &note)?;
+        out.print(0, 2, &last_move, Style::plain())?;
+        let state = if bs.is_game_over() {
+            show_result(bs)
+        } else {
+            preview_turn(bs)
+        };
+        out.print(0, 3, &state, Style::plain())?;
+        // カーソルは盤面の外に置いて表示しない
+        preview_board(&mut out, bs, size, size, 4)?;
+        out.flush()?;
+        bottom.store(5 + size as u16, Ordering::SeqCst);
     }
-
-    // 常時表示
-    execute!(
-        stdout(),
-        EnterAlternateScreen,
-        Clear(ClearType::All),
-        MoveTo(0, 0),
-        Print(" ===== Simple Reversi ===== ".to_string().red().bold()),
-        MoveTo(0, 1),
-        Print(format!("盤面：{0} x {0}", size).to_string()),
-        MoveTo(0, 2),
-        Print(
-            if cpu_flag {
-                "CPU対戦モード"
-            } else if cpu_only_flag {
-                "観戦モード"
-            } else {
-                "1人2役モード"
-            }
-            .to_string()
-        ),
-    )?;
-    // 盤面表示
-    preview_board(&bs, size, size, 4);
-    stdout().flush()?;
-
-    // 結果表示
-    execute!(
-        stdout(),
-        MoveTo(0, 5 + size as u16),
-        Print(show_result(&bs)),
-        MoveTo(0, 7 + size as u16),
-        Print("終了するにはEnterを押してください．")
-    )?;
-
-    loop {
-        let event = read()?;
-
-        if event == Event::Key(KeyCode::Enter.into()) {
-            break;
+    let row = bottom.load(Ordering::SeqCst);
+    if row > 0 {
+        execute!(stdout(), MoveTo(0, row))?;
+    }
+    // RAWモードを抜けてからメッセージを出す
+    drop(terminal);
+    match error {
+        Some(e) => {
+            println!("{}．", e);
+            exit(1);
         }
+        None => println!("配信が終わりました．"),
     }
-
-    // 画面を全消しする
-    execute!(stdout(), Clear(ClearType::All),)?;
-
-    // RAWモードを抜ける
-    disable_raw_mode()?;
-
-    // カーソルを表示に戻す．書式をリセットする．
-    execute!(stdout(), Show, EnableBlinking, ResetColor)?;
-
-    // Alternate Screen を抜ける
-    execute!(stdout(), LeaveAlternateScreen)
+    Ok(())
 }
@@ -0,0 +1,71 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::boardstate::Turn;
+
+/// マス目の位置を表す構造体
+///
+/// xは上から数えた行番号、yは左から数えた列番号（どちらも0始まり）。
+/// ```BoardState```の二次元配列の添字```[x][y]```に対応する。
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Position {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl Position {
+    /// 新しい位置を作成する
+    pub fn new(x: usize, y: usize) -> Position {
+        Position { x, y }
+    }
+}
+
+/// 1手分の着手を表す列挙型
+///
+/// ```Put```はマス目に駒を置く手、```Pass```は置けるマスがないためのパス。
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Move {
+    Put(Position),
+    Pass,
+}
+
+/// 着手した結果
+///
+/// playerが置いた側、positionが置いたマス目、flippedが裏返した駒のマス目。
+/// opponent_passedは相手が置けるマスがなくパスになった（手番が続けてplayerになる）場合にtrue。
+/// game_overは両者ともに置けるマスがなくなりゲームが終了した場合にtrue。
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MoveOutcome {
+    pub player: Turn,
+    pub position: Position,
+    pub flipped: Vec<Position>,
+    pub opponent_passed: bool,
+    pub game_over: bool,
+}
+
+/// 着手できなかった理由
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum MoveError {
+    /// 盤面の範囲外
+    OutOfBounds,
+    /// もう駒が置いてあるマス目
+    Occupied,
+    /// 1つも裏返せないマス目
+    NoFlip,
+    /// ゲームがもう終わっている
+    GameOver,
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            MoveError::OutOfBounds => "盤面の範囲外です",
+            MoveError::Occupied => "もう駒が置いてあります",
+            MoveError::NoFlip => "裏返せる駒がありません",
+            MoveError::GameOver => "ゲームはもう終わっています",
+        };
+        write!(f, "{}", message)
+    }
+}
+
+impl Error for MoveError {}
@@ -0,0 +1,245 @@
+use std::io::{self, BufRead, Write};
+use std::time::Instant;
+
+use crate::boardstate::BoardState;
+use crate::ggf::GgfGame;
+use crate::moves::{Move, Position};
+use crate::search::{disc_difference, score_to_discs, search, Level, SearchLimits};
+
+/// ```set myname```で名乗る名前
+const ENGINE_NAME: &str = "simple-reversi-2";
+
+/// 対応しているNBoardのプロトコルのバージョン
+const PROTOCOL_VERSION: u32 = 2;
+
+/// ```set depth```で受け付ける深さの上限（8x8の空きマスの数）
+const MAX_DEPTH: usize = 60;
+
+/// NBoardのエンジンとして1局分の状態を持ち、GUIからの命令に答える
+///
+/// 命令は1行ずつ```handle```に渡す。盤面は```set game```と```move```でだけ進み、
+/// ```go```で選んだ手はGUIから```move```で送り返されるまで盤面に反映しない。
+#[derive(Clone, Debug)]
+pub struct NboardEngine {
+    board: BoardState,
+    limits: SearchLimits,
+}
+
+impl Default for NboardEngine {
+    fn default() -> NboardEngine {
+        NboardEngine::new()
+    }
+}
+
+impl NboardEngine {
+    /// 8x8の初期局面と「普通」の強さで作成する
+    pub fn new() -> NboardEngine {
+        NboardEngine {
+            board: BoardState::new(4, false),
+            limits: Level::Normal.limits(),
+        }
+    }
+
+    /// 今の盤面
+    pub fn board(&self) -> &BoardState {
+        &self.board
+    }
+
+    /// 探索の打ち切り条件
+    pub fn limits(&self) -> SearchLimits {
+        self.limits
+    }
+
+    /// 1行分の命令を処理して応答をoutに書く
+    ///
+    /// ```quit```を受け取ったら```false```を返す。
+    /// 読めない命令や打てない手には```status```でその旨を返し、盤面は変えない。
+    pub fn handle<W: Write>(&mut self, line: &str, out: &mut W) -> io::Result<bool> {
+        let line = line.trim();
+        let (command, rest) = match line.find(char::is_whitespace) {
+            Some(k) => (&line[..k], line[k..].trim_start()),
+            None => (line, ""),
+        };
+        match command {
+            "" => {}
+            "nboard" => {
+                if rest.parse() != Ok(PROTOCOL_VERSION) {
+                    writeln!(
+                        out,
+                        "status プロトコルのバージョン{}には対応していません",
+                        rest
+                    )?;
+                }
+                writeln!(out, "set myname {}", ENGINE_NAME)?;
+            }
+            "set" => self.set(rest, out)?,
+            "move" => self.play_move(rest, out)?,
+            "go" => self.go(out)?,
+            "hint" => match rest.parse() {
+                Ok(n) => self.hint(n, out)?,
+                Err(_) => writeln!(out, "status hintの後の数が読めません：{}", rest)?,
+            },
+            "ping" => writeln!(out, "pong {}", rest)?,
+            "learn" => writeln!(out, "learned")?,
+            // 解析は対応していないので何もしない
+            "analyze" => {}
+            "quit" => return Ok(false),
+            _ => writeln!(out, "status 不明な命令です：{}", command)?,
+        }
+        out.flush()?;
+        Ok(true)
+    }
+
+    /// ```set depth```、```set game```、```set contempt```を処理する
+    fn set<W: Write>(&mut self, rest: &str, out: &mut W) -> io::Result<()> {
+        let (key, value) = match rest.find(char::is_whitespace) {
+            Some(k) => (&rest[..k], rest[k..].trim_start()),
+            None => (rest, ""),
+        };
+        match key {
+            "depth" => match value.parse::<usize>() {
+                Ok(depth) if (1..=MAX_DEPTH).contains(&depth) => {
+                    // 深さを指定されたときは局面の数では打ち切らない
+                    self.limits = SearchLimits {
+                        depth,
+                        nodes: None,
+                        endgame_empties: self.limits.endgame_empties,
+                    };
+                }
+                _ => writeln!(out, "status 深さは1〜{}で指定してください", MAX_DEPTH)?,
+            },
+            "game" => match value.parse::<GgfGame>().and_then(|game| game.to_board()) {
+                Ok(bs) => self.board = bs,
+                Err(e) => writeln!(out, "status {}", e)?,
+            },
+            // 引き分けの評価は変えないので読み飛ばす
+            "contempt" => {}
+            _ => writeln!(out, "status 不明な設定です：{}", key)?,
+        }
+        Ok(())
+    }
+
+    /// ```move f5/評価値/時間```や```move PA```を処理する
+    ///
+    /// パスは相手の手の後に```BoardState```が自動で記録して手番も渡しているので、
+    /// 最後に記録されたのがパスなら（ゲームが終わった後も含めて）そのパスとして受け付ける。
+    fn play_move<W: Write>(&mut self, rest: &str, out: &mut W) -> io::Result<()> {
+        let square = rest.split('/').next().unwrap_or("").trim();
+        if square.eq_ignore_ascii_case("pa") {
+            if !self.has_passed() {
+                writeln!(out, "status 置けるマス目があるのでパスできません")?;
+            }
+            return Ok(());
+        }
+        match square.parse::<Position>() {
+            Ok(pos) => {
+                if let Err(e) = self.board.try_put(pos.x, pos.y) {
+                    writeln!(out, "status {}には打てません（{}）", pos, e)?;
+                }
+            }
+            Err(_) => writeln!(out, "status 手が読めません：{}", square)?,
+        }
+        Ok(())
+    }
+
+    /// 直前にパスが記録されているか、ゲームが終わっているか
+    fn has_passed(&self) -> bool {
+        if self.board.is_game_over() {
+            return true;
+        }
+        match self.board.history().last() {
+            Some(entry) => entry.mv == Move::Pass,
+            None => false,
+        }
+    }
+
+    /// 手番の側の最善手を```=== f5/評価値/時間```で返す（置けなければ```=== PA```）
+    fn go<W: Write>(&mut self, out: &mut W) -> io::Result<()> {
+        writeln!(out, "status 考えています")?;
+        let start = Instant::now();
+        let result = search(&self.board, self.limits);
+        let elapsed = start.elapsed().as_secs_f64();
+        writeln!(out, "nodestats {} {:.3}", result.nodes, elapsed)?;
+        match result.best_move {
+            Some(pos) => writeln!(
+                out,
+                "=== {}/{:.2}/{:.3}",
+                square_name(pos),
+                score_to_discs(result.score),
+                elapsed
+            )?,
+            None => writeln!(out, "=== PA")?,
+        }
+        writeln!(out, "status")
+    }
+
+    /// 置けるマス目のうち評価の良いものからn個を```search```で返す
+    ///
+    /// それぞれの手を打った後の局面を1手浅く読んで評価する。
+    /// 置けるマス目がなければパスを```search PA```で返す（評価はその局面の石差）。
+    fn hint<W: Write>(&mut self, n: usize, out: &mut W) -> io::Result<()> {
+        writeln!(out, "status 考えています")?;
+        let me = self.board.turn();
+        if self.board.legal_moves().is_empty() {
+            writeln!(
+                out,
+                "search PA {:.2} 0 {}",
+                disc_difference(&self.board, me) as f64,
+                self.limits.depth
+            )?;
+            return writeln!(out, "status");
+        }
+        let limits = SearchLimits {
+            depth: self.limits.depth.saturating_sub(1).max(1),
+            ..self.limits
+        };
+        let mut scored: Vec<(Position, f64)> = self
+            .board
+            .legal_moves()
+            .into_iter()
+            .map(|pos| {
+                let mut next = self.board.clone();
+                next.put(pos.x, pos.y);
+                let score = if next.is_game_over() {
+                    disc_difference(&next, me) as f64
+                } else {
+                    let discs = score_to_discs(search(&next, limits).score);
+                    // 相手がパスになったときは手番が自分のままなので符号を反転しない
+                    if next.turn() == me {
+                        discs
+                    } else {
+                        -discs
+                    }
+                };
+                (pos, score)
+            })
+            .collect();
+        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+        for (pos, score) in scored.into_iter().take(n) {
+            writeln!(
+                out,
+                "search {} {:.2} 0 {}",
+                square_name(pos),
+                score,
+                self.limits.depth
+            )?;
+        }
+        writeln!(out, "status")
+    }
+}
+
+/// NBoardで使う大文字のマス目の表記
+fn square_name(pos: Position) -> String {
+    pos.to_string().to_uppercase()
+}
+
+/// inputから命令を1行ずつ読んで、応答をoutに書く（```quit```か入力の終わりまで）
+pub fn run<R: BufRead, W: Write>(input: R, mut out: W) -> io::Result<()> {
+    let mut engine = NboardEngine::new();
+    for line in input.lines() {
+        if !engine.handle(&line?, &mut out)? {
+            break;
+        }
+    }
+    Ok(())
+}
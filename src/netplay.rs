@@ -0,0 +1,290 @@
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{Shutdown, TcpListener, TcpStream, ToSocketAddrs};
+use std::str::FromStr;
+
+use crate::boardstate::{BoardState, Turn};
+use crate::moves::Position;
+use crate::player::{Action, Player};
+
+/// 通信の手順のバージョン（```hello```で伝え合い、違えば対局しない）
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// ```--host```や```--join```でポート番号を省略したときに使うポート番号
+pub const DEFAULT_PORT: u16 = 7878;
+
+/// 通信対戦で受け付ける盤面のサイズの上限（列名が1文字のアルファベットで収まる大きさ）
+pub const MAX_SIZE: usize = 26;
+
+/// 通信対戦でやり取りする1行分のメッセージ
+///
+/// ```text
+/// hello 1          （接続した直後に両者が送る、数は通信の手順のバージョン）
+/// game 8 black     （待ち受けた側が送る、盤面のサイズと待ち受けた側の色）
+/// move f5          （自分の手を打ったら送る、パスは送らない）
+/// quit             （ゲームを途中で終わるときに送る）
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Message {
+    Hello(u32),
+    Game { size: usize, host: Turn },
+    Move(Position),
+    Quit,
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Message::Hello(version) => write!(f, "hello {}", version),
+            Message::Game { size, host } => {
+                let color = match host {
+                    Turn::Black => "black",
+                    Turn::White => "white",
+                };
+                write!(f, "game {} {}", size, color)
+            }
+            Message::Move(pos) => write!(f, "move {}", pos),
+            Message::Quit => write!(f, "quit"),
+        }
+    }
+}
+
+impl FromStr for Message {
+    type Err = NetError;
+
+    fn from_str(s: &str) -> Result<Message, NetError> {
+        let protocol_error = || NetError::Protocol(s.to_string());
+        let tokens: Vec<&str> = s.split_whitespace().collect();
+        match tokens[..] {
+            ["hello", version] => version
+                .parse()
+                .map(Message::Hello)
+                .map_err(|_| protocol_error()),
+            ["game", size, color] => {
+                let size: usize = size.parse().map_err(|_| protocol_error())?;
+                // 壊れた相手から巨大な盤面を送られても作らないようにする
+                if !(4..=MAX_SIZE).contains(&size) || !size.is_multiple_of(2) {
+                    return Err(protocol_error());
+                }
+                let host = match color {
+                    "black" => Turn::Black,
+                    "white" => Turn::White,
+                    _ => return Err(protocol_error()),
+                };
+                Ok(Message::Game { size, host })
+            }
+            ["move", square] => square
+                .parse()
+                .map(Message::Move)
+                .map_err(|_| protocol_error()),
+            ["quit"] => Ok(Message::Quit),
+            _ => Err(protocol_error()),
+        }
+    }
+}
+
+/// 通信対戦を続けられなくなった理由
+#[derive(Debug)]
+pub enum NetError {
+    /// 接続や送受信に失敗した
+    Io(io::Error),
+    /// 相手との接続が切れた
+    Disconnected,
+    /// 相手がゲームを途中で終わった
+    Quit,
+    /// 読めないメッセージや、順番のおかしいメッセージが届いた
+    Protocol(String),
+    /// 通信の手順のバージョンが合わない（相手のバージョンを添える）
+    Version(u32),
+    /// 相手から打てない手が届いた
+    IllegalMove(Position),
+}
+
+impl fmt::Display for NetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NetError::Io(e) => write!(f, "通信に失敗しました（{}）", e),
+            NetError::Disconnected => write!(f, "相手との接続が切れました"),
+            NetError::Quit => write!(f, "相手がゲームを終了しました"),
+            NetError::Protocol(s) => write!(f, "相手から読めないメッセージが届きました：{}", s),
+            NetError::Version(v) => write!(
+                f,
+                "相手の通信のバージョン（{}）がこちら（{}）と違います",
+                v, PROTOCOL_VERSION
+            ),
+            NetError::IllegalMove(pos) => write!(f, "相手から打てない手（{}）が届きました", pos),
+        }
+    }
+}
+
+impl Error for NetError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            NetError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for NetError {
+    fn from(e: io::Error) -> NetError {
+        NetError::Io(e)
+    }
+}
+
+/// 相手との接続（1行ずつメッセージを送受信する）
+#[derive(Debug)]
+pub struct Connection {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl Connection {
+    /// 接続済みのソケットから作成する
+    pub fn new(stream: TcpStream) -> io::Result<Connection> {
+        // 1手ずつすぐに届くように、まとめて送るのをやめておく
+        stream.set_nodelay(true)?;
+        Ok(Connection {
+            reader: BufReader::new(stream.try_clone()?),
+            writer: stream,
+        })
+    }
+
+    /// 同じ接続を指す別の```Connection```を作る（送る側と受け取る側で分けて持つのに使う）
+    ///
+    /// 受け取り済みで読んでいない分は複製されないので、受け取るのはどちらか一方だけにすること。
+    pub fn try_clone(&self) -> io::Result<Connection> {
+        Connection::new(self.writer.try_clone()?)
+    }
+
+    /// メッセージを1つ送る
+    pub fn send(&mut self, message: Message) -> Result<(), NetError> {
+        writeln!(self.writer, "{}", message)?;
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    /// 接続を閉じる
+    ///
+    /// 同じ接続を指す```Connection```すべてに効くので、別のスレッドで```recv```を待っているところは
+    /// ```NetError::Disconnected```で返る。
+    pub fn shutdown(&self) -> io::Result<()> {
+        self.writer.shutdown(Shutdown::Both)
+    }
+
+    /// メッセージを1つ受け取る（届くまで待つ）
+    ///
+    /// 空行は読み飛ばす。接続が切れていたら```NetError::Disconnected```。
+    pub fn recv(&mut self) -> Result<Message, NetError> {
+        loop {
+            let mut line = String::new();
+            match self.reader.read_line(&mut line) {
+                Ok(0) => return Err(NetError::Disconnected),
+                Ok(_) if line.trim().is_empty() => continue,
+                Ok(_) => return line.trim().parse(),
+                Err(e) if e.kind() == io::ErrorKind::ConnectionReset => {
+                    return Err(NetError::Disconnected)
+                }
+                Err(e) => return Err(NetError::Io(e)),
+            }
+        }
+    }
+}
+
+/// 対局の準備ができた接続
+///
+/// sizeは盤面のサイズ、my_colorはこちら側の色。
+#[derive(Debug)]
+pub struct NetGame {
+    pub size: usize,
+    pub my_color: Turn,
+    pub connection: Connection,
+}
+
+/// ```hello```を受け取ってバージョンを確かめる
+fn expect_hello(connection: &mut Connection) -> Result<(), NetError> {
+    match connection.recv()? {
+        Message::Hello(PROTOCOL_VERSION) => Ok(()),
+        Message::Hello(v) => Err(NetError::Version(v)),
+        m => Err(NetError::Protocol(m.to_string())),
+    }
+}
+
+/// 待ち受けているところに相手が来るのを待ち、盤面のサイズと色を伝えて対局を始める
+///
+/// host_colorは待ち受けた側（こちら）の色。
+pub fn accept_game(
+    listener: &TcpListener,
+    size: usize,
+    host_color: Turn,
+) -> Result<NetGame, NetError> {
+    let (stream, _) = listener.accept()?;
+    let mut connection = Connection::new(stream)?;
+    connection.send(Message::Hello(PROTOCOL_VERSION))?;
+    connection.send(Message::Game {
+        size,
+        host: host_color,
+    })?;
+    expect_hello(&mut connection)?;
+    Ok(NetGame {
+        size,
+        my_color: host_color,
+        connection,
+    })
+}
+
+/// 待ち受けている相手に接続し、盤面のサイズと色を受け取って対局を始める
+pub fn join_game<A: ToSocketAddrs>(addr: A) -> Result<NetGame, NetError> {
+    let mut connection = Connection::new(TcpStream::connect(addr)?)?;
+    expect_hello(&mut connection)?;
+    let (size, host) = match connection.recv()? {
+        Message::Game { size, host } => (size, host),
+        m => return Err(NetError::Protocol(m.to_string())),
+    };
+    connection.send(Message::Hello(PROTOCOL_VERSION))?;
+    Ok(NetGame {
+        size,
+        my_color: host.opponent(),
+        connection,
+    })
+}
+
+/// 通信相手の指し手
+///
+/// 相手から```move```が届くまで待ち、今の盤面に打てる手かを```cnt_reversable```で確かめてから返す。
+/// 接続が切れたときや打てない手が届いたときは、```NetError```を包んだ```io::Error```を返す。
+#[derive(Debug)]
+pub struct RemotePlayer {
+    connection: Connection,
+}
+
+impl RemotePlayer {
+    /// 相手からのメッセージを受け取る接続を指定して作成する
+    pub fn new(connection: Connection) -> RemotePlayer {
+        RemotePlayer { connection }
+    }
+
+    /// 相手の手を受け取る
+    pub fn receive_move(&mut self, bs: &BoardState) -> Result<Position, NetError> {
+        match self.connection.recv()? {
+            Message::Move(pos) => {
+                let n = bs.get_size();
+                if pos.x >= n || pos.y >= n || bs.cnt_reversable()[pos.x][pos.y] == 0 {
+                    return Err(NetError::IllegalMove(pos));
+                }
+                Ok(pos)
+            }
+            Message::Quit => Err(NetError::Quit),
+            m => Err(NetError::Protocol(m.to_string())),
+        }
+    }
+}
+
+impl Player for RemotePlayer {
+    fn play(&mut self, bs: &BoardState) -> io::Result<Action> {
+        self.receive_move(bs)
+            .map(Action::Put)
+            .map_err(io::Error::other)
+    }
+}
@@ -0,0 +1,149 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::moves::{Move, MoveError, Position};
+
+/// アルファベットの個数
+const LETTERS: usize = 26;
+
+/// 棋譜の表記を読めなかった理由
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum NotationError {
+    /// マス目の表記として読めない文字があった（先頭から何文字目かは0始まり）
+    Syntax(usize),
+    /// 盤面に打てない手があった（何手目かは1始まり）
+    IllegalMove(usize, Position, MoveError),
+}
+
+impl fmt::Display for NotationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotationError::Syntax(k) => {
+                write!(f, "{}文字目をマス目の表記として読めません", k + 1)
+            }
+            NotationError::IllegalMove(k, pos, e) => {
+                write!(f, "{}手目の{}には打てません（{}）", k, pos, e)
+            }
+        }
+    }
+}
+
+impl Error for NotationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            NotationError::IllegalMove(_, _, e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// 列番号（0始まり）をアルファベットの列名にする
+///
+/// a〜zの次はaa, ab, ...と続く（表計算ソフトの列名と同じ）。
+pub fn column_name(y: usize) -> String {
+    let mut name: Vec<u8> = Vec::new();
+    let mut k = y + 1;
+    while k > 0 {
+        k -= 1;
+        name.push(b'a' + (k % LETTERS) as u8);
+        k /= LETTERS;
+    }
+    name.reverse();
+    String::from_utf8(name).unwrap()
+}
+
+/// アルファベットの列名を列番号（0始まり）にする（大文字も受け付ける）
+pub fn column_index(name: &str) -> Option<usize> {
+    if name.is_empty() {
+        return None;
+    }
+    name.chars()
+        .try_fold(0usize, |k, c| {
+            if !c.is_ascii_alphabetic() {
+                return None;
+            }
+            let d = (c.to_ascii_lowercase() as u8 - b'a') as usize + 1;
+            k.checked_mul(LETTERS)?.checked_add(d)
+        })
+        .map(|k| k - 1)
+}
+
+/// 文字列のstart文字目（0始まり）から1マス分の表記を読む
+///
+/// 返り値は読んだマス目と読み終わった位置。
+fn parse_square(chars: &[char], start: usize) -> Result<(Position, usize), NotationError> {
+    let letters_end = start
+        + chars[start..]
+            .iter()
+            .take_while(|c| c.is_ascii_alphabetic())
+            .count();
+    let digits_end = letters_end
+        + chars[letters_end..]
+            .iter()
+            .take_while(|c| c.is_ascii_digit())
+            .count();
+    let letters: String = chars[start..letters_end].iter().collect();
+    let digits: String = chars[letters_end..digits_end].iter().collect();
+
+    let y = column_index(&letters).ok_or(NotationError::Syntax(start))?;
+    let row: usize = digits
+        .parse()
+        .map_err(|_| NotationError::Syntax(letters_end))?;
+    if row == 0 {
+        return Err(NotationError::Syntax(letters_end));
+    }
+    Ok((Position::new(row - 1, y), digits_end))
+}
+
+/// 棋譜を読む
+///
+/// ```f5d6c3```のようにマス目の表記（列名のアルファベットと1始まりの行番号）を並べたもの。
+/// 間の空白は読み飛ばし、大文字も受け付ける。パスは書かない（打てる手がなければ自動でパスになる）。
+pub fn parse_transcript(s: &str) -> Result<Vec<Position>, NotationError> {
+    let chars: Vec<char> = s.chars().collect();
+    let mut moves: Vec<Position> = Vec::new();
+    let mut k = 0;
+    while k < chars.len() {
+        if chars[k].is_whitespace() {
+            k += 1;
+            continue;
+        }
+        let (pos, next) = parse_square(&chars, k)?;
+        moves.push(pos);
+        k = next;
+    }
+    Ok(moves)
+}
+
+/// 着手の列を棋譜にする（パスは書かない）
+pub fn format_transcript(moves: &[Move]) -> String {
+    moves
+        .iter()
+        .filter_map(|mv| match mv {
+            Move::Put(pos) => Some(pos.to_string()),
+            Move::Pass => None,
+        })
+        .collect()
+}
+
+impl fmt::Display for Position {
+    /// ```f5```のような表記で表示する（列名のアルファベットと1始まりの行番号）
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", column_name(self.y), self.x + 1)
+    }
+}
+
+impl FromStr for Position {
+    type Err = NotationError;
+
+    /// ```f5```のような表記を読む
+    fn from_str(s: &str) -> Result<Position, NotationError> {
+        let chars: Vec<char> = s.chars().collect();
+        let (pos, end) = parse_square(&chars, 0)?;
+        if end != chars.len() {
+            return Err(NotationError::Syntax(end));
+        }
+        Ok(pos)
+    }
+}
@@ -0,0 +1,136 @@
+use std::io;
+
+use rand::rngs::SmallRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+use crate::boardstate::BoardState;
+use crate::moves::Position;
+use crate::search::{search, Level, SearchLimits};
+
+/// 手番の側が選んだ行動
+///
+/// ```Put```はそのマス目に置く手。```Undo```（待った）、```Redo```（やり直し）、
+/// ```Save```（ゲームを保存する）、```Quit```（ゲームを終わる）は人間が操作する側
+/// （```screen```で画面を操作する指し手）だけが返す。
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Action {
+    Put(Position),
+    Undo,
+    Redo,
+    Save,
+    Quit,
+}
+
+/// 指し手の種類
+///
+/// 保存したゲームを再開するときなど、指し手そのものではなく種類だけを覚えておくのに使う。
+/// ```Remote```は通信対戦の相手（手は```netplay::RemotePlayer```が受け取る）。
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum PlayerKind {
+    Human,
+    Random,
+    Search(Level),
+    Remote,
+}
+
+impl PlayerKind {
+    /// 人間が操作するかどうか
+    pub fn is_human(self) -> bool {
+        self == PlayerKind::Human
+    }
+}
+
+/// 局面を受け取って次の行動を決める指し手
+///
+/// 黒と白にそれぞれ1つずつ割り当てれば、人間とCPU、CPU同士など、どの組み合わせでも対局できる。
+/// ```play```は置けるマス目がある局面でだけ呼ばれる（パスは```BoardState```が自動で処理する）。
+pub trait Player {
+    /// 手番の局面を受け取り、次の行動を決める
+    fn play(&mut self, bs: &BoardState) -> io::Result<Action>;
+
+    /// 人間が操作するかどうか（待ったで戻す手数などに使う）
+    fn is_human(&self) -> bool {
+        false
+    }
+}
+
+/// 置けるマス目からランダムに選ぶCPU
+///
+/// 裏返せる駒が多いマス目ほど選ばれやすくし、隅に置けるときは隅から選ぶ。
+/// 乱数は渡された```SmallRng```だけから取るので、同じシードなら同じ手を選ぶ。
+#[derive(Clone, Debug)]
+pub struct RandomPlayer {
+    rng: SmallRng,
+}
+
+impl RandomPlayer {
+    /// 乱数生成器を指定して作成する
+    pub fn new(rng: SmallRng) -> RandomPlayer {
+        RandomPlayer { rng }
+    }
+
+    /// シードを指定して作成する
+    pub fn from_seed(seed: u64) -> RandomPlayer {
+        RandomPlayer::new(SmallRng::seed_from_u64(seed))
+    }
+
+    /// 置くマス目を選ぶ（置けるマス目がなければ```None```）
+    pub fn choose(&mut self, bs: &BoardState) -> Option<Position> {
+        // 置けるマス目を重み付けしつつVecで管理
+        let mut options: Vec<Position> = Vec::new();
+        let mut options_corners: Vec<Position> = Vec::new();
+        let n = bs.get_size();
+        for (i, row) in bs.cnt_reversable().iter().enumerate() {
+            for (j, &c) in row.iter().enumerate() {
+                for _ in 0..c {
+                    options.push(Position::new(i, j));
+                }
+                if c > 0 && (i == 0 || i == n - 1) && (j == 0 || j == n - 1) {
+                    options_corners.push(Position::new(i, j));
+                }
+            }
+        }
+
+        if options_corners.is_empty() {
+            options.choose(&mut self.rng).copied()
+        } else {
+            options_corners.choose(&mut self.rng).copied()
+        }
+    }
+}
+
+impl Player for RandomPlayer {
+    fn play(&mut self, bs: &BoardState) -> io::Result<Action> {
+        Ok(Action::Put(
+            self.choose(bs).expect("置けるマス目がありません"),
+        ))
+    }
+}
+
+/// アルファベータ法で探索するCPU
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct SearchPlayer {
+    limits: SearchLimits,
+}
+
+impl SearchPlayer {
+    /// 打ち切り条件を指定して作成する
+    pub fn new(limits: SearchLimits) -> SearchPlayer {
+        SearchPlayer { limits }
+    }
+
+    /// 強さを指定して作成する
+    pub fn with_level(level: Level) -> SearchPlayer {
+        SearchPlayer::new(level.limits())
+    }
+}
+
+impl Player for SearchPlayer {
+    fn play(&mut self, bs: &BoardState) -> io::Result<Action> {
+        let pos = search(bs, self.limits)
+            .best_move
+            .expect("置けるマス目がありません");
+        Ok(Action::Put(pos))
+    }
+}
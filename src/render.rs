@@ -0,0 +1,321 @@
+use std::io::Write;
+
+use crossterm::cursor::MoveTo;
+use crossterm::style::{self, Attribute, ContentStyle, Print, PrintStyledContent};
+use crossterm::terminal::{Clear, ClearType};
+use crossterm::{queue, Result};
+
+/// 画面で使う文字の色
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Color {
+    Red,
+    Blue,
+    Yellow,
+}
+
+impl From<Color> for style::Color {
+    fn from(color: Color) -> style::Color {
+        match color {
+            Color::Red => style::Color::Red,
+            Color::Blue => style::Color::Blue,
+            Color::Yellow => style::Color::Yellow,
+        }
+    }
+}
+
+/// 文字の書式
+///
+/// fgは文字の色、bgは背景の色で、```None```ならターミナルの既定の色のまま。
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Style {
+    pub bold: bool,
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+}
+
+impl Style {
+    /// 書式なし
+    pub fn plain() -> Style {
+        Style::default()
+    }
+
+    /// 太字
+    pub fn bold() -> Style {
+        Style {
+            bold: true,
+            ..Style::default()
+        }
+    }
+
+    /// 文字の色を変えたもの
+    pub fn with_fg(self, color: Color) -> Style {
+        Style {
+            fg: Some(color),
+            ..self
+        }
+    }
+
+    /// 背景の色を変えたもの
+    pub fn with_bg(self, color: Color) -> Style {
+        Style {
+            bg: Some(color),
+            ..self
+        }
+    }
+
+    /// crosstermの書式に直す
+    fn to_content_style(self) -> ContentStyle {
+        let mut style = ContentStyle::new();
+        if self.bold {
+            style = style.attribute(Attribute::Bold);
+        }
+        if let Some(color) = self.fg {
+            style = style.foreground(color.into());
+        }
+        if let Some(color) = self.bg {
+            style = style.background(color.into());
+        }
+        style
+    }
+}
+
+/// 画面を描く先
+///
+/// 画面はすべてこれを通して描くので、ターミナルの代わりに```MemoryRenderer```に描けば
+/// 何が表示されるかをテストで確かめられる。
+/// 行と列は0始まりで、列は半角1文字分を1と数える（全角文字は2）。
+pub trait Renderer {
+    /// 画面を全部消す
+    fn clear(&mut self) -> Result<()>;
+
+    /// row行目を消す
+    fn clear_line(&mut self, row: u16) -> Result<()>;
+
+    /// row行目のcol列目から文字列を書く
+    fn print(&mut self, col: u16, row: u16, text: &str, style: Style) -> Result<()>;
+
+    /// 書いたものを画面に反映する
+    fn flush(&mut self) -> Result<()>;
+}
+
+/// ターミナルに描く
+///
+/// 書いたものはキューに溜めておき、```flush```でまとめて出力する。
+#[derive(Debug)]
+pub struct CrosstermRenderer<W: Write> {
+    out: W,
+}
+
+impl<W: Write> CrosstermRenderer<W> {
+    /// outに描く（普通は```stdout()```）
+    pub fn new(out: W) -> CrosstermRenderer<W> {
+        CrosstermRenderer { out }
+    }
+}
+
+impl<W: Write> Renderer for CrosstermRenderer<W> {
+    fn clear(&mut self) -> Result<()> {
+        queue!(self.out, Clear(ClearType::All))
+    }
+
+    fn clear_line(&mut self, row: u16) -> Result<()> {
+        queue!(self.out, MoveTo(0, row), Clear(ClearType::CurrentLine))
+    }
+
+    fn print(&mut self, col: u16, row: u16, text: &str, style: Style) -> Result<()> {
+        queue!(self.out, MoveTo(col, row))?;
+        if style == Style::plain() {
+            queue!(self.out, Print(text))
+        } else {
+            queue!(
+                self.out,
+                PrintStyledContent(style.to_content_style().apply(text))
+            )
+        }
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+/// 文字が画面で占める列の数（全角文字なら2）
+///
+/// このゲームで表示する文字（かな、漢字、全角の記号）を見分けられれば十分なので、
+/// 東アジアの文字の主な範囲だけを全角として扱う。
+pub fn char_width(c: char) -> u16 {
+    match c as u32 {
+        0x1100..=0x115F
+        | 0x2E80..=0x303E
+        | 0x3040..=0x33FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xA000..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6 => 2,
+        _ => 1,
+    }
+}
+
+/// メモリ上の画面の1マス（全角文字は2マス目を```Tail```にする）
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Cell {
+    Blank,
+    Char(char, Style),
+    Tail,
+}
+
+/// メモリ上の文字の格子に描く（テスト用）
+///
+/// 画面の大きさは決めず、書いたところまで広がる。
+#[derive(Clone, Debug, Default)]
+pub struct MemoryRenderer {
+    rows: Vec<Vec<Cell>>,
+}
+
+impl MemoryRenderer {
+    /// 何も書いていない画面を作る
+    pub fn new() -> MemoryRenderer {
+        MemoryRenderer::default()
+    }
+
+    /// row行目の文字列（末尾の空白は除く）
+    pub fn line(&self, row: u16) -> String {
+        let cells = match self.rows.get(row as usize) {
+            Some(cells) => cells,
+            None => return String::new(),
+        };
+        let line: String = cells
+            .iter()
+            .filter_map(|cell| match cell {
+                Cell::Blank => Some(' '),
+                Cell::Char(c, _) => Some(*c),
+                Cell::Tail => None,
+            })
+            .collect();
+        line.trim_end().to_string()
+    }
+
+    /// 全部の行の文字列（末尾の空行は除く）
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines: Vec<String> = (0..self.rows.len() as u16).map(|r| self.line(r)).collect();
+        while lines.last().is_some_and(|line| line.is_empty()) {
+            lines.pop();
+        }
+        lines
+    }
+
+    /// 画面全体を改行でつないだ文字列（スナップショットとの比較用）
+    pub fn text(&self) -> String {
+        self.lines().join("\n")
+    }
+
+    /// row行目のcol列目の文字の書式（全角文字の2列目はその文字の書式、何もなければ書式なし）
+    pub fn style_at(&self, col: u16, row: u16) -> Style {
+        let cells = match self.rows.get(row as usize) {
+            Some(cells) => cells,
+            None => return Style::plain(),
+        };
+        let mut col = col as usize;
+        if cells.get(col) == Some(&Cell::Tail) && col > 0 {
+            col -= 1;
+        }
+        match cells.get(col) {
+            Some(Cell::Char(_, style)) => *style,
+            _ => Style::plain(),
+        }
+    }
+
+    /// 文字列が最初に現れる位置を（列、行）で返す（行をまたぐものは探さない）
+    pub fn find(&self, text: &str) -> Option<(u16, u16)> {
+        let needle: Vec<char> = text.chars().collect();
+        for (row, cells) in self.rows.iter().enumerate() {
+            // 文字とその列の組（全角文字の2列目は飛ばす）
+            let chars: Vec<(usize, char)> = cells
+                .iter()
+                .enumerate()
+                .filter_map(|(col, cell)| match cell {
+                    Cell::Blank => Some((col, ' ')),
+                    Cell::Char(c, _) => Some((col, *c)),
+                    Cell::Tail => None,
+                })
+                .collect();
+            if needle.is_empty() || needle.len() > chars.len() {
+                continue;
+            }
+            for start in 0..=chars.len() - needle.len() {
+                if chars[start..start + needle.len()]
+                    .iter()
+                    .map(|&(_, c)| c)
+                    .eq(needle.iter().copied())
+                {
+                    return Some((chars[start].0 as u16, row as u16));
+                }
+            }
+        }
+        None
+    }
+
+    /// col列目にかかっている文字を消す（全角文字の片方だけを上書きするときはもう片方も空白にする）
+    fn erase(cells: &mut [Cell], col: usize) {
+        match cells[col] {
+            Cell::Tail => {
+                cells[col - 1] = Cell::Blank;
+                cells[col] = Cell::Blank;
+            }
+            Cell::Char(c, _) if char_width(c) == 2 => {
+                cells[col] = Cell::Blank;
+                if let Some(next) = cells.get_mut(col + 1) {
+                    *next = Cell::Blank;
+                }
+            }
+            _ => cells[col] = Cell::Blank,
+        }
+    }
+}
+
+impl Renderer for MemoryRenderer {
+    fn clear(&mut self) -> Result<()> {
+        self.rows.clear();
+        Ok(())
+    }
+
+    fn clear_line(&mut self, row: u16) -> Result<()> {
+        if let Some(cells) = self.rows.get_mut(row as usize) {
+            cells.clear();
+        }
+        Ok(())
+    }
+
+    fn print(&mut self, col: u16, row: u16, text: &str, style: Style) -> Result<()> {
+        let row = row as usize;
+        if self.rows.len() <= row {
+            self.rows.resize(row + 1, Vec::new());
+        }
+        let cells = &mut self.rows[row];
+        let mut col = col as usize;
+        for c in text.chars() {
+            let width = char_width(c) as usize;
+            if cells.len() < col + width {
+                cells.resize(col + width, Cell::Blank);
+            }
+            for k in 0..width {
+                MemoryRenderer::erase(cells, col + k);
+            }
+            cells[col] = Cell::Char(c, style);
+            if width == 2 {
+                cells[col + 1] = Cell::Tail;
+            }
+            col += width;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,244 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::boardstate::{BoardState, Turn};
+use crate::moves::{Move, MoveError, Position};
+use crate::player::PlayerKind;
+use crate::search::Level;
+
+/// 保存ファイルの1行目
+const HEADER: &str = "simple-reversi-2 save";
+
+/// 保存したゲーム
+///
+/// sizeは盤面のサイズ、black/whiteはそれぞれの側の指し手、movesは初期局面（黒番）からの着手の列
+/// （パスも含む）、turnは保存した時点の手番。
+///
+/// ファイルには次のような1行1項目のテキストとして書き出す。
+///
+/// ```text
+/// simple-reversi-2 save
+/// size 8
+/// black human
+/// white search normal
+/// turn black
+/// move 4 5
+/// move 3 5
+/// pass
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SavedGame {
+    pub size: usize,
+    pub black: PlayerKind,
+    pub white: PlayerKind,
+    pub moves: Vec<Move>,
+    pub turn: Turn,
+}
+
+/// 保存ファイルを読み込めなかった理由
+#[derive(Debug)]
+pub enum SaveFileError {
+    /// ファイルの読み書きに失敗した
+    Io(io::Error),
+    /// 書式が正しくない行があった（行番号は1始まり）
+    Syntax(usize, String),
+    /// 必要な項目がなかった
+    Missing(&'static str),
+    /// 盤面に打てない手があった（何手目かは1始まり）
+    IllegalMove(usize, MoveError),
+    /// 着手を並べた結果の手番が保存された手番と合わない
+    TurnMismatch,
+}
+
+impl fmt::Display for SaveFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveFileError::Io(e) => write!(f, "ファイルの読み書きに失敗しました（{}）", e),
+            SaveFileError::Syntax(line, text) => {
+                write!(f, "{}行目の書式が正しくありません：{}", line, text)
+            }
+            SaveFileError::Missing(key) => write!(f, "{}の項目がありません", key),
+            SaveFileError::IllegalMove(k, e) => write!(f, "{}手目が打てません（{}）", k, e),
+            SaveFileError::TurnMismatch => write!(f, "保存された手番が盤面と合いません"),
+        }
+    }
+}
+
+impl Error for SaveFileError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SaveFileError::Io(e) => Some(e),
+            SaveFileError::IllegalMove(_, e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for SaveFileError {
+    fn from(e: io::Error) -> SaveFileError {
+        SaveFileError::Io(e)
+    }
+}
+
+/// 指し手の種類を保存ファイル用の文字列にする
+fn kind_to_str(kind: PlayerKind) -> &'static str {
+    match kind {
+        // 通信相手も人間なので、保存したゲームは1人2役で再開する
+        PlayerKind::Human | PlayerKind::Remote => "human",
+        PlayerKind::Random => "random",
+        PlayerKind::Search(Level::Weak) => "search weak",
+        PlayerKind::Search(Level::Normal) => "search normal",
+        PlayerKind::Search(Level::Strong) => "search strong",
+    }
+}
+
+/// 保存ファイル用の文字列から指し手の種類を読む
+fn kind_from_str(s: &str) -> Option<PlayerKind> {
+    match s {
+        "human" => Some(PlayerKind::Human),
+        "random" => Some(PlayerKind::Random),
+        "search weak" => Some(PlayerKind::Search(Level::Weak)),
+        "search normal" => Some(PlayerKind::Search(Level::Normal)),
+        "search strong" => Some(PlayerKind::Search(Level::Strong)),
+        _ => None,
+    }
+}
+
+/// 手番を保存ファイル用の文字列にする
+fn turn_to_str(turn: Turn) -> &'static str {
+    match turn {
+        Turn::Black => "black",
+        Turn::White => "white",
+    }
+}
+
+impl SavedGame {
+    /// 今の盤面と両者の指し手から保存するゲームを作る（初期局面は黒番であること）
+    ///
+    /// 待ったで取り消した手は保存しない。
+    pub fn new(bs: &BoardState, black: PlayerKind, white: PlayerKind) -> SavedGame {
+        SavedGame {
+            size: bs.get_size(),
+            black,
+            white,
+            moves: bs.history().iter().map(|entry| entry.mv).collect(),
+            turn: bs.turn(),
+        }
+    }
+
+    /// 初期局面から着手を並べ直して盤面を作る
+    ///
+    /// パスは```BoardState```が自動で記録するので、並べ直すときは読み飛ばす。
+    pub fn to_board(&self) -> Result<BoardState, SaveFileError> {
+        let mut bs = BoardState::new(self.size / 2, false);
+        for (k, mv) in self.moves.iter().enumerate() {
+            if let Move::Put(pos) = mv {
+                bs.try_put(pos.x, pos.y)
+                    .map_err(|e| SaveFileError::IllegalMove(k + 1, e))?;
+            }
+        }
+        if bs.turn() != self.turn {
+            return Err(SaveFileError::TurnMismatch);
+        }
+        Ok(bs)
+    }
+
+    /// ファイルに書き出す
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), SaveFileError> {
+        fs::write(path, self.to_string())?;
+        Ok(())
+    }
+
+    /// ファイルから読み込む
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<SavedGame, SaveFileError> {
+        fs::read_to_string(path)?.parse()
+    }
+}
+
+impl fmt::Display for SavedGame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", HEADER)?;
+        writeln!(f, "size {}", self.size)?;
+        writeln!(f, "black {}", kind_to_str(self.black))?;
+        writeln!(f, "white {}", kind_to_str(self.white))?;
+        writeln!(f, "turn {}", turn_to_str(self.turn))?;
+        for mv in &self.moves {
+            match mv {
+                Move::Put(pos) => writeln!(f, "move {} {}", pos.x, pos.y)?,
+                Move::Pass => writeln!(f, "pass")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for SavedGame {
+    type Err = SaveFileError;
+
+    /// 保存ファイルの内容を読む（空行は読み飛ばす）
+    fn from_str(s: &str) -> Result<SavedGame, SaveFileError> {
+        let mut size: Option<usize> = None;
+        let mut black: Option<PlayerKind> = None;
+        let mut white: Option<PlayerKind> = None;
+        let mut turn: Option<Turn> = None;
+        let mut moves: Vec<Move> = Vec::new();
+
+        let mut lines = s.lines().enumerate().filter(|(_, l)| !l.trim().is_empty());
+        match lines.next() {
+            Some((_, l)) if l.trim() == HEADER => {}
+            Some((i, l)) => return Err(SaveFileError::Syntax(i + 1, l.to_string())),
+            None => return Err(SaveFileError::Missing(HEADER)),
+        }
+
+        for (i, line) in lines {
+            let syntax_error = || SaveFileError::Syntax(i + 1, line.to_string());
+            let line = line.trim();
+            let (key, value) = match line.find(' ') {
+                Some(k) => (&line[..k], line[k + 1..].trim()),
+                None => (line, ""),
+            };
+            match key {
+                "size" => {
+                    let n: usize = value.parse().map_err(|_| syntax_error())?;
+                    if n < 4 || !n.is_multiple_of(2) {
+                        return Err(syntax_error());
+                    }
+                    size = Some(n);
+                }
+                "black" => black = Some(kind_from_str(value).ok_or_else(syntax_error)?),
+                "white" => white = Some(kind_from_str(value).ok_or_else(syntax_error)?),
+                "turn" => {
+                    turn = Some(match value {
+                        "black" => Turn::Black,
+                        "white" => Turn::White,
+                        _ => return Err(syntax_error()),
+                    })
+                }
+                "move" => {
+                    let xy: Vec<usize> = value
+                        .split_whitespace()
+                        .map(|v| v.parse().map_err(|_| syntax_error()))
+                        .collect::<Result<_, _>>()?;
+                    match xy[..] {
+                        [x, y] => moves.push(Move::Put(Position::new(x, y))),
+                        _ => return Err(syntax_error()),
+                    }
+                }
+                "pass" if value.is_empty() => moves.push(Move::Pass),
+                _ => return Err(syntax_error()),
+            }
+        }
+
+        Ok(SavedGame {
+            size: size.ok_or(SaveFileError::Missing("size"))?,
+            black: black.ok_or(SaveFileError::Missing("black"))?,
+            white: white.ok_or(SaveFileError::Missing("white"))?,
+            moves,
+            turn: turn.ok_or(SaveFileError::Missing("turn"))?,
+        })
+    }
+}
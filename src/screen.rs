@@ -0,0 +1,1352 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::fs::OpenOptions;
+use std::io::{self, stdout, Write};
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use crossterm::cursor::{EnableBlinking, Hide, Show};
+use crossterm::style::ResetColor;
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use crossterm::{execute, ErrorKind, Result};
+
+use crate::boardstate::{BoardState, Turn};
+use crate::ggf::GgfGame;
+use crate::input::{InputSource, Key, TerminalInput};
+use crate::moves::{MoveOutcome, Position};
+use crate::netplay::{Connection, Message, NetGame, RemotePlayer};
+use crate::player::{Action, Player, PlayerKind, RandomPlayer, SearchPlayer};
+use crate::render::{Color, CrosstermRenderer, Renderer, Style};
+use crate::savefile::SavedGame;
+use crate::search::Level;
+use crate::spectate::SpectatorServer;
+
+/// どの画面でも一番上に出すタイトル
+const TITLE: &str = " ===== Simple Reversi ===== ";
+
+/// 盤面を表示し始める行
+const BOARD_ROW: u16 = 4;
+
+/// 保存したゲームのファイル名
+const SAVE_FILE: &str = "simple-reversi-2.save";
+
+/// 棋譜を書き足していくGGFファイルのファイル名
+const GGF_FILE: &str = "simple-reversi-2.ggf";
+
+/// CPU対戦モードで選べるCPUの強さ（```Random```は今まで通りのランダムなCPU）
+const CPU_LEVELS: [PlayerKind; 4] = [
+    PlayerKind::Random,
+    PlayerKind::Search(Level::Weak),
+    PlayerKind::Search(Level::Normal),
+    PlayerKind::Search(Level::Strong),
+];
+
+/// CPU対戦モードで最初に選ばれている強さ（```CPU_LEVELS```の番号）
+const DEFAULT_LEVEL: usize = 2;
+
+/// 整数の入力が不正である旨のメッセージ
+fn err_not_int() -> &'static str {
+    "半角数字で整数を入力してください．"
+}
+
+/// 入力が不適切な旨のメッセージ
+fn err_input() -> &'static str {
+    "入力が不適切です．"
+}
+
+/// カーソル位置の書式（黄色の背景に青の太字）
+fn cursor_style() -> Style {
+    Style::bold().with_fg(Color::Blue).with_bg(Color::Yellow)
+}
+
+/// 盤面をrow_now行目から描く（カーソル位置は黄色の背景に青の太字）
+///
+/// カーソルを出さないときは盤面の外（```cursor_x```を盤面のサイズ以上）にする。
+pub fn preview_board(
+    out: &mut dyn Renderer,
+    bs: &BoardState,
+    cursor_x: usize,
+    cursor_y: usize,
+    row_now: u16,
+) -> Result<()> {
+    draw_cells(out, &bs.show_board(), cursor_x, cursor_y, row_now)
+}
+
+/// 置けるマス目に+印をつけて盤面をrow_now行目から描く（カーソル位置は黄色の背景に青の太字）
+pub fn preview_board_with_help(
+    out: &mut dyn Renderer,
+    bs: &BoardState,
+    cursor_x: usize,
+    cursor_y: usize,
+    row_now: u16,
+) -> Result<()> {
+    let mut v = bs.show_board();
+    let cnt = bs.cnt_reversable();
+    for (row, cnt_row) in v.iter_mut().zip(cnt.iter()) {
+        for (c, &n) in row.iter_mut().zip(cnt_row.iter()) {
+            if n > 0 {
+                *c = '+';
+            }
+        }
+    }
+    draw_cells(out, &v, cursor_x, cursor_y, row_now)
+}
+
+/// 盤面の各マスの文字を1マスにつき2列使って描く
+fn draw_cells(
+    out: &mut dyn Renderer,
+    v: &[Vec<char>],
+    cursor_x: usize,
+    cursor_y: usize,
+    row_now: u16,
+) -> Result<()> {
+    for (i, row) in v.iter().enumerate() {
+        let r = row_now + i as u16;
+        out.clear_line(r)?;
+        for (j, &c) in row.iter().enumerate() {
+            let style = if i == cursor_x && j == cursor_y {
+                cursor_style()
+            } else {
+                Style::plain()
+            };
+            out.print(2 * j as u16, r, " ", Style::plain())?;
+            out.print(2 * j as u16 + 1, r, &c.to_string(), style)?;
+        }
+    }
+    Ok(())
+}
+
+/// どちらのターンかを表示する
+pub fn preview_turn(bs: &BoardState) -> String {
+    format!("{}のターン．", bs.which_turn())
+}
+
+/// 結果を表示する
+pub fn show_result(bs: &BoardState) -> String {
+    let ((c1, s1), (c2, s2)) = bs.count_pieces();
+    if s1 > s2 {
+        format!("{0}が{1}個，{2}が{3}個で{0}の勝ち！", c1, s1, c2, s2)
+    } else if s1 < s2 {
+        format!("{0}が{1}個，{2}が{3}個で{2}の勝ち！", c1, s1, c2, s2)
+    } else {
+        format!("{0}が{1}個，{2}が{3}個で引き分け！", c1, s1, c2, s2)
+    }
+}
+
+/// CPUの強さの表示名
+pub fn level_label(kind: PlayerKind) -> &'static str {
+    match kind {
+        PlayerKind::Search(level) => level.name(),
+        PlayerKind::Random => "ランダム",
+        PlayerKind::Human => "人間",
+        PlayerKind::Remote => "通信相手",
+    }
+}
+
+/// 常時表示するモード名（黒と白の指し手の種類から決める）
+fn mode_label(kinds: [PlayerKind; 2]) -> String {
+    match kinds {
+        [PlayerKind::Human, PlayerKind::Human] => "1人2役モード".to_string(),
+        [PlayerKind::Human, PlayerKind::Remote] | [PlayerKind::Remote, PlayerKind::Human] => {
+            "通信対戦モード".to_string()
+        }
+        [PlayerKind::Human, cpu] | [cpu, PlayerKind::Human] => {
+            format!("CPU対戦モード（{}）", level_label(cpu))
+        }
+        _ => "観戦モード".to_string(),
+    }
+}
+
+/// GGFの棋譜に書く対局者の名前
+fn ggf_name(kind: PlayerKind) -> &'static str {
+    match kind {
+        PlayerKind::Human => "human",
+        PlayerKind::Remote => "remote",
+        PlayerKind::Random => "cpu-random",
+        PlayerKind::Search(Level::Weak) => "cpu-weak",
+        PlayerKind::Search(Level::Normal) => "cpu-normal",
+        PlayerKind::Search(Level::Strong) => "cpu-strong",
+    }
+}
+
+/// ファイルの末尾に1行書き足す（ファイルがなければ作る）
+fn append_line(path: &Path, line: &str) -> io::Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", line)
+}
+
+/// 相手がパスになった場合にその側の駒を返す
+fn passed_piece_of(outcome: &MoveOutcome) -> Option<char> {
+    if outcome.opponent_passed {
+        Some(outcome.player.opponent().piece())
+    } else {
+        None
+    }
+}
+
+/// 手番の側の指し手の番号（黒が0、白が1）
+fn side_of(bs: &BoardState) -> usize {
+    if bs.is_it_white_turn() {
+        1
+    } else {
+        0
+    }
+}
+
+/// ターミナルを元に戻す（代替スクリーンを使っていたときはそこからも抜ける）
+///
+/// パニックしたときにも呼ぶので、失敗しても何もしない。
+pub fn restore_terminal(alternate_screen: bool) {
+    disable_raw_mode().ok();
+    execute!(stdout(), Show, EnableBlinking, ResetColor).ok();
+    if alternate_screen {
+        execute!(stdout(), LeaveAlternateScreen).ok();
+    }
+}
+
+/// パニックしたときに呼ばれるフック
+type PanicHook = Box<dyn Fn(&panic::PanicHookInfo<'_>) + Sync + Send + 'static>;
+
+/// ターミナルをRAWモードにしてカーソルを隠しておくガード
+///
+/// 捨てたときに（エラーで抜けたときも含めて）ターミナルを元に戻す。
+/// パニックしたときはメッセージが見えるように、表示する前に元に戻す。
+/// previous_hookは```enter```の前に設定されていたフックで、捨てたときに設定し直す。
+pub struct TerminalGuard {
+    alternate_screen: bool,
+    previous_hook: Option<Arc<PanicHook>>,
+}
+
+impl fmt::Debug for TerminalGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TerminalGuard")
+            .field("alternate_screen", &self.alternate_screen)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TerminalGuard {
+    /// RAWモードに入り、alternate_screenがtrueなら代替スクリーンにも入る
+    pub fn enter(alternate_screen: bool) -> Result<TerminalGuard> {
+        let previous_hook = Arc::new(panic::take_hook());
+        let hook = Arc::clone(&previous_hook);
+        panic::set_hook(Box::new(move |info| {
+            restore_terminal(alternate_screen);
+            hook(info);
+        }));
+        // 途中で失敗してもここまでの分を戻せるように、先にガードを作っておく
+        let guard = TerminalGuard {
+            alternate_screen,
+            previous_hook: Some(previous_hook),
+        };
+        if alternate_screen {
+            execute!(stdout(), EnterAlternateScreen)?;
+        }
+        enable_raw_mode()?;
+        execute!(stdout(), Hide)?;
+        Ok(guard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        // パニックしたときはフックで元に戻してあり、ここで画面を消すとメッセージまで消えてしまう
+        if thread::panicking() {
+            return;
+        }
+        if self.alternate_screen {
+            execute!(stdout(), Clear(ClearType::All)).ok();
+        }
+        restore_terminal(self.alternate_screen);
+
+        // 自分のフックを外してから、enterの前に設定されていたフックに戻す
+        drop(panic::take_hook());
+        if let Some(previous_hook) = self.previous_hook.take() {
+            match Arc::try_unwrap(previous_hook) {
+                Ok(hook) => panic::set_hook(hook),
+                Err(hook) => panic::set_hook(Box::new(move |info| hook(info))),
+            }
+        }
+    }
+}
+
+/// 通信相手の手を待つ間、inputからキー入力を見張る
+///
+/// stopのフラグが立つまで少しずつ```InputSource::wait```で待ち、
+/// 終了するキーが押されたら相手に終了を伝えて接続を閉じ、手を待っているところを切り上げさせる。
+/// 返り値は終了するキーが押されたかどうか。
+fn watch_quit_keys(
+    input: &mut dyn InputSource,
+    mut connection: Connection,
+    stop: &AtomicBool,
+) -> bool {
+    while !stop.load(Ordering::SeqCst) {
+        match input.wait(Duration::from_millis(50)) {
+            Ok(true) => {
+                connection.send(Message::Quit).ok();
+                connection.shutdown().ok();
+                return true;
+            }
+            Ok(false) => {}
+            Err(_) => return false,
+        }
+    }
+    false
+}
+
+/// 選択肢の並べ方（縦なら↑↓キー、横なら←→キーで選ぶ）
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Layout {
+    Vertical,
+    Horizontal,
+}
+
+/// 選ばれている選択肢をキーに合わせて動かす（端では止まる）
+///
+/// 並べ方の向きの矢印キーでなければ```None```。
+fn select(key: Key, selected: usize, len: usize, layout: Layout) -> Option<usize> {
+    match (layout, key) {
+        (Layout::Vertical, Key::Up) | (Layout::Horizontal, Key::Left) => {
+            Some(selected.saturating_sub(1))
+        }
+        (Layout::Vertical, Key::Down) | (Layout::Horizontal, Key::Right) => {
+            Some((selected + 1).min(len - 1))
+        }
+        _ => None,
+    }
+}
+
+/// 選択肢を（列、行、表示）の位置に描く（選ばれているものは青の太字）
+fn draw_choices(
+    out: &mut dyn Renderer,
+    choices: &[(u16, u16, String)],
+    selected: usize,
+) -> Result<()> {
+    for (k, (col, row, label)) in choices.iter().enumerate() {
+        let style = if k == selected {
+            Style::bold().with_fg(Color::Blue)
+        } else {
+            Style::plain()
+        };
+        out.print(*col, *row, label, style)?;
+    }
+    Ok(())
+}
+
+/// 画面を消して見出し（タイトル、盤面のサイズ、モード）を描く
+///
+/// 盤面のサイズやモードがまだ決まっていなければその行は出さない。
+/// noteは盤面のサイズに続けて出すお知らせ（観戦用の配信先など）。
+pub fn draw_header(
+    out: &mut dyn Renderer,
+    size: Option<usize>,
+    kinds: Option<[PlayerKind; 2]>,
+    note: &str,
+) -> Result<()> {
+    out.clear()?;
+    out.print(0, 0, TITLE, Style::bold().with_fg(Color::Red))?;
+    if let Some(size) = size {
+        out.print(
+            0,
+            1,
+            &format!("盤面：{0} x {0}{1}", size, note),
+            Style::plain(),
+        )?;
+    }
+    if let Some(kinds) = kinds {
+        out.print(0, 2, &mode_label(kinds), Style::plain())?;
+    }
+    Ok(())
+}
+
+/// 結果の画面を描く（seedはその対局のCPUの乱数のシード）
+///
+/// net_messageは通信対戦を続けられなくなった理由で、あれば赤の太字で出す。
+pub fn draw_result(
+    out: &mut dyn Renderer,
+    bs: &BoardState,
+    kinds: [PlayerKind; 2],
+    seed: u64,
+    net_message: Option<&str>,
+) -> Result<()> {
+    let size = bs.get_size();
+    draw_header(out, Some(size), Some(kinds), "")?;
+    // カーソルは盤面の外に置いて表示しない
+    preview_board(out, bs, size, size, BOARD_ROW)?;
+    let size = size as u16;
+    out.print(0, 5 + size, &show_result(bs), Style::plain())?;
+    out.print(
+        0,
+        6 + size,
+        &format!("シード：{0}（--seed {0} で同じ対局を再現できます）", seed),
+        Style::plain(),
+    )?;
+    out.print(
+        0,
+        7 + size,
+        "gキーで棋譜をGGF形式で保存できます．終了するにはEnterを押してください．",
+        Style::plain(),
+    )?;
+    if let Some(message) = net_message {
+        out.print(0, 9 + size, message, Style::bold().with_fg(Color::Red))?;
+    }
+    Ok(())
+}
+
+/// 画面とそれを描く人間の指し手とで分け合う描き先
+///
+/// 中の```Renderer```に描くだけなので、```App```と```HumanPlayer```のどちらから描いても同じ画面になる。
+#[derive(Clone)]
+struct SharedRenderer<'a>(Rc<RefCell<&'a mut dyn Renderer>>);
+
+impl Renderer for SharedRenderer<'_> {
+    fn clear(&mut self) -> Result<()> {
+        self.0.borrow_mut().clear()
+    }
+
+    fn clear_line(&mut self, row: u16) -> Result<()> {
+        self.0.borrow_mut().clear_line(row)
+    }
+
+    fn print(&mut self, col: u16, row: u16, text: &str, style: Style) -> Result<()> {
+        self.0.borrow_mut().print(col, row, text, style)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.0.borrow_mut().flush()
+    }
+}
+
+/// 画面とそれを操作する人間の指し手とで分け合うキー入力の読み元
+type SharedInput<'a> = Rc<RefCell<&'a mut dyn InputSource>>;
+
+/// 人間の側のカーソル
+///
+/// xが盤面のサイズならヒントを見る項目、サイズ+1ならゲームを終わる項目を選んでいる。
+/// with_helpは置けるマス目に+印をつけて表示するかどうか。
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+struct Cursor {
+    x: usize,
+    y: usize,
+    with_help: bool,
+}
+
+/// 画面で操作する人間の指し手
+///
+/// 盤面とカーソル、盤面の下の項目をoutに描き、inputから読んだキーでカーソルを動かして行動を決める。
+/// 終了するキーが押されたときは、それが分かるように```io::ErrorKind::Interrupted```のエラーを返す。
+struct HumanPlayer<'a> {
+    out: SharedRenderer<'a>,
+    input: SharedInput<'a>,
+    cursor: Cursor,
+}
+
+impl<'a> HumanPlayer<'a> {
+    /// outに描いてinputから読む（カーソルは左上から始める）
+    fn new(out: SharedRenderer<'a>, input: SharedInput<'a>) -> HumanPlayer<'a> {
+        HumanPlayer {
+            out,
+            input,
+            cursor: Cursor::default(),
+        }
+    }
+
+    /// 盤面と項目を描く（movedがtrueなら「そこには置けません」などのメッセージを消す）
+    fn draw(&mut self, bs: &BoardState, moved: bool) -> Result<()> {
+        let size = bs.get_size();
+        let cursor = self.cursor;
+        if cursor.with_help {
+            preview_board_with_help(&mut self.out, bs, cursor.x, cursor.y, BOARD_ROW)?;
+        } else {
+            preview_board(&mut self.out, bs, cursor.x, cursor.y, BOARD_ROW)?;
+        }
+        draw_choices(
+            &mut self.out,
+            &[
+                (
+                    0,
+                    4 + size as u16,
+                    "駒が置ける場所のヒントを見る".to_string(),
+                ),
+                (0, 5 + size as u16, "ゲームを終わって結果を見る".to_string()),
+            ],
+            // 盤面の上にカーソルがあるときはどちらも選ばれていない
+            cursor.x.wrapping_sub(size),
+        )?;
+        if moved {
+            self.out.clear_line(6 + size as u16)?;
+        }
+        self.out.flush()
+    }
+
+    /// キー入力でカーソルを動かし、行動が決まったらそれを返す
+    fn read_action(&mut self, bs: &BoardState) -> Result<Action> {
+        let size = bs.get_size();
+        self.draw(bs, false)?;
+        loop {
+            let key = self.input.borrow_mut().read_key()?;
+            let cursor = &mut self.cursor;
+            match key {
+                Key::Up => cursor.x = cursor.x.saturating_sub(1),
+                Key::Down => cursor.x = (cursor.x + 1).min(size + 1),
+                Key::Left => cursor.y = cursor.y.saturating_sub(1),
+                Key::Right => cursor.y = (cursor.y + 1).min(size - 1),
+                // 待った
+                Key::Char('u') => return Ok(Action::Undo),
+                // 待ったで取り消した手をやり直す
+                Key::Char('r') => return Ok(Action::Redo),
+                // ゲームを保存する
+                Key::Char('s') => return Ok(Action::Save),
+                // 終了処理
+                Key::Enter if cursor.x == size + 1 => return Ok(Action::Quit),
+                // ヘルプ表示処理
+                Key::Enter if cursor.x == size => cursor.with_help = true,
+                Key::Enter => {
+                    cursor.with_help = false;
+                    return Ok(Action::Put(Position::new(cursor.x, cursor.y)));
+                }
+                Key::Quit => {
+                    return Err(ErrorKind::IoError(io::Error::new(
+                        io::ErrorKind::Interrupted,
+                        "終了するキーが押されました",
+                    )))
+                }
+                // 使わないキーでは描き直さない
+                _ => continue,
+            }
+            self.draw(bs, true)?;
+        }
+    }
+}
+
+impl Player for HumanPlayer<'_> {
+    fn play(&mut self, bs: &BoardState) -> io::Result<Action> {
+        self.read_action(bs).map_err(|e| match e {
+            ErrorKind::IoError(e) => e,
+            e => io::Error::other(e),
+        })
+    }
+
+    fn is_human(&self) -> bool {
+        true
+    }
+}
+
+/// 対局中のゲーム（対局、終了の確認、結果の画面で持ち回る）
+///
+/// playersは黒と白の指し手で、通信相手との接続を閉じるために結果の画面に移るときに捨てる。
+/// not_puttableとsave_messageは次に画面を描き直すときに1回だけ出すメッセージ、
+/// passed_pieceは直前の着手でパスになった側の駒、net_messageは通信対戦を続けられなくなった理由。
+struct Match<'a> {
+    size: usize,
+    kinds: [PlayerKind; 2],
+    bs: BoardState,
+    players: [Option<Box<dyn Player + 'a>>; 2],
+    connection: Option<Connection>,
+    not_puttable: bool,
+    save_message: Option<String>,
+    passed_piece: Option<char>,
+    net_message: Option<String>,
+}
+
+impl<'a> Match<'a> {
+    /// 盤面と黒と白の指し手の種類から対局を始める
+    ///
+    /// 人間の指し手はoutに描いてinputから読み、通信相手の指し手はconnectionから手を受け取る。
+    /// ランダムなCPUは白のシードをずらして、観戦モードで両者が同じ手を選ばないようにする。
+    fn new(
+        kinds: [PlayerKind; 2],
+        bs: BoardState,
+        connection: Option<Connection>,
+        seed: u64,
+        out: &SharedRenderer<'a>,
+        input: &SharedInput<'a>,
+    ) -> io::Result<Match<'a>> {
+        let mut players: [Option<Box<dyn Player + 'a>>; 2] = [None, None];
+        for (k, &kind) in kinds.iter().enumerate() {
+            players[k] = match (kind, &connection) {
+                (PlayerKind::Human, _) => {
+                    Some(Box::new(HumanPlayer::new(out.clone(), Rc::clone(input))))
+                }
+                (PlayerKind::Random, _) => Some(Box::new(RandomPlayer::from_seed(
+                    seed.wrapping_add(k as u64),
+                ))),
+                (PlayerKind::Search(level), _) => Some(Box::new(SearchPlayer::with_level(level))),
+                (PlayerKind::Remote, Some(conn)) => {
+                    Some(Box::new(RemotePlayer::new(conn.try_clone()?)))
+                }
+                (PlayerKind::Remote, None) => unreachable!("通信相手の指し手は接続から作る"),
+            };
+        }
+        Ok(Match {
+            size: bs.get_size(),
+            kinds,
+            bs,
+            players,
+            connection,
+            not_puttable: false,
+            save_message: None,
+            passed_piece: None,
+            net_message: None,
+        })
+    }
+
+    /// 手番の側の指し手の種類
+    fn turn_kind(&self) -> PlayerKind {
+        self.kinds[side_of(&self.bs)]
+    }
+
+    /// 手番の側の指し手が人間かどうか（結果の画面に移った後は```false```）
+    fn turn_is_human(&self) -> bool {
+        self.players[side_of(&self.bs)]
+            .as_ref()
+            .is_some_and(|player| player.is_human())
+    }
+
+    /// 通信対戦なら相手に終了を伝える（もう切れていても構わない）
+    fn send_quit(&mut self) {
+        if let Some(conn) = self.connection.as_mut() {
+            conn.send(Message::Quit).ok();
+        }
+    }
+}
+
+/// 画面（状態機械の状態）
+///
+/// 選択肢のある画面のitemは選ばれている選択肢の番号。
+enum Screen<'a> {
+    /// 保存したゲームを再開するか新しく始めるかを選ぶ
+    SavedGame { item: usize },
+    /// 盤面のサイズを入力する（noticeは入力欄の上に出すお知らせ、errorは入力が不正だった旨）
+    Size {
+        notice: Option<String>,
+        input: String,
+        error: Option<&'static str>,
+    },
+    /// モードを選ぶ（levelはCPU対戦モードのCPUの強さの```CPU_LEVELS```の番号）
+    Mode {
+        size: usize,
+        item: usize,
+        level: usize,
+    },
+    /// CPU対戦モードで黒と白のどちらで始めるかを選ぶ（kindsは黒を選んだときの指し手の種類）
+    Color {
+        size: usize,
+        kinds: [PlayerKind; 2],
+        item: usize,
+    },
+    /// 対局中
+    Playing(Box<Match<'a>>),
+    /// ゲームを終わるかどうかを確かめる（0が「はい」）
+    ConfirmQuit(Box<Match<'a>>, usize),
+    /// 結果（棋譜を保存したときはその結果のメッセージも出す）
+    Result(Box<Match<'a>>, Option<String>),
+}
+
+/// 画面でキーを処理したり手を進めたりした後の行き先
+enum Next<'a> {
+    /// 画面を全部描き直す（別の画面に移るときや局面が変わったとき）
+    Redraw(Screen<'a>),
+    /// 変わったところだけ描き直す（結果の画面で棋譜を保存したときなど）
+    Refresh(Screen<'a>),
+    /// 描き直さずに次のキーを待つ（使わないキーが押されたとき）
+    Ignore(Screen<'a>),
+    /// ゲームを終わる
+    Exit,
+}
+
+/// ゲームの外から与えるもの
+///
+/// seedはCPUの乱数のシード。
+/// netが指定されたときは保存したゲームやモードの選択を飛ばして通信対戦をする。
+/// spectatorsが指定されたときは局面が変わるたびに観戦者に配信する。
+/// save_fileとggf_fileはゲームを保存するファイルと棋譜を書き足すファイル。
+pub struct Session {
+    pub seed: u64,
+    pub net: Option<NetGame>,
+    pub spectators: Option<SpectatorServer>,
+    pub save_file: PathBuf,
+    pub ggf_file: PathBuf,
+}
+
+impl Session {
+    /// 通信も配信もせず、ファイルは今のディレクトリの決まった名前のものを使う
+    pub fn new(seed: u64) -> Session {
+        Session {
+            seed,
+            net: None,
+            spectators: None,
+            save_file: PathBuf::from(SAVE_FILE),
+            ggf_file: PathBuf::from(GGF_FILE),
+        }
+    }
+}
+
+/// ターミナルでゲームをする（seedはCPUの乱数のシード）
+///
+/// netとspectatorsについては```Session```を参照。
+/// 終了するキーが押されたときやエラーで抜けるときも、ターミナルは元に戻す。
+pub fn run(seed: u64, net: Option<NetGame>, spectators: Option<SpectatorServer>) -> Result<()> {
+    let _terminal = TerminalGuard::enter(true)?;
+    let session = Session {
+        net,
+        spectators,
+        ..Session::new(seed)
+    };
+    run_with(
+        session,
+        &mut CrosstermRenderer::new(stdout()),
+        &mut TerminalInput,
+    )
+}
+
+/// outに画面を描き、inputからキー入力を読んでゲームをする
+///
+/// 画面を描いてからキー入力（CPUや通信相手の番ならその手）を待ち、次の画面に移るのを繰り返す。
+/// ターミナルの準備はしないので、テストではメモリ上の画面と用意したキー入力で動かせる。
+pub fn run_with(
+    session: Session,
+    out: &mut dyn Renderer,
+    input: &mut dyn InputSource,
+) -> Result<()> {
+    let Session {
+        seed,
+        net,
+        spectators,
+        save_file,
+        ggf_file,
+    } = session;
+
+    let mut app = App {
+        seed,
+        spectators,
+        save_file,
+        ggf_file,
+        out: SharedRenderer(Rc::new(RefCell::new(out))),
+        input: Rc::new(RefCell::new(input)),
+    };
+
+    // 通信対戦ならこちらの色を人間に、相手の色を通信相手にする
+    // そうでなければ保存したゲームがあれば再開するかどうかを選び、なければ新しく始める
+    let mut screen = if let Some(game) = net {
+        let kinds = match game.my_color {
+            Turn::Black => [PlayerKind::Human, PlayerKind::Remote],
+            Turn::White => [PlayerKind::Remote, PlayerKind::Human],
+        };
+        let bs = BoardState::new(game.size / 2, false);
+        Screen::Playing(Box::new(app.new_match(kinds, bs, Some(game.connection))?))
+    } else if app.save_file.exists() {
+        Screen::SavedGame { item: 0 }
+    } else {
+        Screen::Size {
+            notice: None,
+            input: String::new(),
+            error: None,
+        }
+    };
+
+    let mut full = true;
+    let mut draw = true;
+    loop {
+        if draw {
+            app.draw(&screen, full)?;
+        }
+        let next = match screen {
+            Screen::Playing(m) => app.play_turn(m)?,
+            screen => {
+                let key = app.input.borrow_mut().read_key()?;
+                app.handle_key(screen, key)?
+            }
+        };
+        screen = match next {
+            Next::Redraw(screen) => {
+                full = true;
+                draw = true;
+                screen
+            }
+            Next::Refresh(screen) => {
+                full = false;
+                draw = true;
+                screen
+            }
+            Next::Ignore(screen) => {
+                draw = false;
+                screen
+            }
+            Next::Exit => return Ok(()),
+        };
+    }
+}
+
+/// 画面をまたいで持ち回るもの
+///
+/// seedはCPUの乱数のシード、spectatorsは観戦者への配信（結果の画面に移るときに閉じる）、
+/// save_fileとggf_fileはゲームと棋譜を保存するファイル、outは画面を描く先、inputはキー入力を読む元
+/// （outとinputは対局中の人間の指し手と分け合う）。
+struct App<'a> {
+    seed: u64,
+    spectators: Option<SpectatorServer>,
+    save_file: PathBuf,
+    ggf_file: PathBuf,
+    out: SharedRenderer<'a>,
+    input: SharedInput<'a>,
+}
+
+impl<'a> App<'a> {
+    /// 画面を描く（fullがfalseなら変わったところだけ描き直す）
+    fn draw(&mut self, screen: &Screen<'a>, full: bool) -> Result<()> {
+        match screen {
+            Screen::SavedGame { item } => self.draw_saved_game(*item)?,
+            Screen::Size {
+                notice,
+                input,
+                error,
+            } => self.draw_size(notice.as_deref(), input, *error)?,
+            Screen::Mode { size, item, level } => self.draw_mode(*size, *item, *level)?,
+            Screen::Color { size, kinds, item } => self.draw_color(*size, *kinds, *item)?,
+            Screen::Playing(m) => self.draw_playing(m, full)?,
+            Screen::ConfirmQuit(m, item) => self.draw_confirm_quit(m, *item)?,
+            Screen::Result(m, message) => self.draw_result(m, message.as_deref(), full)?,
+        }
+        self.out.flush()
+    }
+
+    /// キー入力を今の画面に渡して次の画面を決める（対局中のキー入力は人間の指し手が読む）
+    ///
+    /// 終了するキーはどの画面でもゲームを終わらせる（通信対戦なら相手にも伝える）。
+    fn handle_key(&mut self, screen: Screen<'a>, key: Key) -> Result<Next<'a>> {
+        if key == Key::Quit {
+            if let Screen::ConfirmQuit(mut m, _) = screen {
+                m.send_quit();
+            }
+            return Ok(Next::Exit);
+        }
+        match screen {
+            Screen::SavedGame { item } => self.saved_game_key(item, key),
+            Screen::Size {
+                notice,
+                input,
+                error,
+            } => Ok(size_key(notice, input, error, key)),
+            Screen::Mode { size, item, level } => self.mode_key(size, item, level, key),
+            Screen::Color { size, kinds, item } => self.color_key(size, kinds, item, key),
+            Screen::Playing(_) => unreachable!("対局中のキー入力は人間の指し手が読む"),
+            Screen::ConfirmQuit(m, item) => Ok(self.confirm_quit_key(m, item, key)),
+            Screen::Result(m, message) => Ok(result_key(m, message, key, &self.ggf_file)),
+        }
+    }
+
+    /// 画面を消して見出しを描く（観戦用に配信していれば盤面のサイズに続けて配信先を出す）
+    fn draw_header(&mut self, size: Option<usize>, kinds: Option<[PlayerKind; 2]>) -> Result<()> {
+        let note = match &self.spectators {
+            Some(server) => format!(
+                "　観戦用に{}で配信中（{}人）",
+                server.local_addr(),
+                server.spectators()
+            ),
+            None => String::new(),
+        };
+        draw_header(&mut self.out, size, kinds, &note)
+    }
+
+    /// 保存したゲームを再開するかどうかの画面
+    fn draw_saved_game(&mut self, item: usize) -> Result<()> {
+        self.draw_header(None, None)?;
+        self.out.print(
+            0,
+            2,
+            "保存したゲームがあります．↑↓キーで選択，Enterキーで決定，Escキーで終了．",
+            Style::plain(),
+        )?;
+        draw_choices(
+            &mut self.out,
+            &[
+                (0, 3, "保存したゲームを再開する".to_string()),
+                (0, 4, "新しく始める".to_string()),
+            ],
+            item,
+        )
+    }
+
+    /// 再開を選んだら保存したゲームを読み込み、読み込めなかったらその旨を出して新しく始める
+    fn saved_game_key(&mut self, item: usize, key: Key) -> Result<Next<'a>> {
+        if let Some(item) = select(key, item, 2, Layout::Vertical) {
+            return Ok(Next::Redraw(Screen::SavedGame { item }));
+        }
+        if key != Key::Enter {
+            return Ok(Next::Ignore(Screen::SavedGame { item }));
+        }
+        let mut notice = None;
+        if item == 0 {
+            match SavedGame::load(&self.save_file).and_then(|saved| Ok((saved.to_board()?, saved)))
+            {
+                Ok((bs, saved)) => {
+                    let m = self.new_match([saved.black, saved.white], bs, None)?;
+                    return Ok(Next::Redraw(Screen::Playing(Box::new(m))));
+                }
+                Err(e) => notice = Some(format!("保存したゲームを読み込めませんでした：{}．", e)),
+            }
+        }
+        Ok(Next::Redraw(Screen::Size {
+            notice,
+            input: String::new(),
+            error: None,
+        }))
+    }
+
+    /// 盤面のサイズを入力する画面
+    fn draw_size(&mut self, notice: Option<&str>, input: &str, error: Option<&str>) -> Result<()> {
+        self.draw_header(None, None)?;
+        let mut row = 1;
+        if let Some(notice) = notice {
+            self.out.print(0, row, notice, Style::plain())?;
+            row += 1;
+        }
+        self.out.print(
+            0,
+            row,
+            "盤面のサイズを4以上の偶数で半角数字で入力してください．Enterキーで確定，Escキーで終了します．",
+            Style::plain(),
+        )?;
+        self.out
+            .print(0, row + 1, &format!("> {}", input), Style::plain())?;
+        if let Some(error) = error {
+            self.out.print(0, row + 2, error, Style::plain())?;
+        }
+        Ok(())
+    }
+
+    /// モードを選ぶ画面
+    fn draw_mode(&mut self, size: usize, item: usize, level: usize) -> Result<()> {
+        self.draw_header(Some(size), None)?;
+        self.out.print(
+            0,
+            2,
+            "モードを選択してください．↑↓キーで選択，Enterキーで決定，Escキーで終了（CPU対戦モードは←→キーで強さを選択）．",
+            Style::plain(),
+        )?;
+        let level = level_label(CPU_LEVELS[level]);
+        let cpu = if item == 0 {
+            format!("CPU対戦モード ← {} →", level)
+        } else {
+            format!("CPU対戦モード（{}）", level)
+        };
+        draw_choices(
+            &mut self.out,
+            &[
+                (0, 3, cpu),
+                (0, 4, "観戦モード".to_string()),
+                (0, 5, "1人2役モード".to_string()),
+            ],
+            item,
+        )
+    }
+
+    /// CPU対戦モードなら色を選ぶ画面に、そうでなければ対局に進む
+    fn mode_key(&mut self, size: usize, item: usize, level: usize, key: Key) -> Result<Next<'a>> {
+        if let Some(item) = select(key, item, 3, Layout::Vertical) {
+            return Ok(Next::Redraw(Screen::Mode { size, item, level }));
+        }
+        // CPU対戦モードを選んでいるときは←→キーで強さを選ぶ
+        if item == 0 {
+            if let Some(level) = select(key, level, CPU_LEVELS.len(), Layout::Horizontal) {
+                return Ok(Next::Redraw(Screen::Mode { size, item, level }));
+            }
+        }
+        if key != Key::Enter {
+            return Ok(Next::Ignore(Screen::Mode { size, item, level }));
+        }
+        // CPU対戦モードではひとまず人間を黒にしておく
+        match item {
+            0 => Ok(Next::Redraw(Screen::Color {
+                size,
+                kinds: [PlayerKind::Human, CPU_LEVELS[level]],
+                item: 0,
+            })),
+            1 => self.start(size, [PlayerKind::Random, PlayerKind::Random]),
+            _ => self.start(size, [PlayerKind::Human, PlayerKind::Human]),
+        }
+    }
+
+    /// CPU対戦モードで黒と白のどちらで始めるかを選ぶ画面
+    fn draw_color(&mut self, size: usize, kinds: [PlayerKind; 2], item: usize) -> Result<()> {
+        self.draw_header(Some(size), Some(kinds))?;
+        self.out.print(
+            0,
+            3,
+            &format!(
+                "{0}と{1}，どちらから始めますか？ {0}が先攻です．←→キーで選択，Enterキーで決定，Escキーで終了．",
+                BoardState::black_piece(),
+                BoardState::white_piece()
+            ),
+            Style::plain(),
+        )?;
+        draw_choices(
+            &mut self.out,
+            &[
+                (3, 4, BoardState::black_piece().to_string()),
+                (6, 4, BoardState::white_piece().to_string()),
+            ],
+            item,
+        )
+    }
+
+    /// 選んだ色で対局に進む（白を選んだら黒と白の指し手を入れ替える）
+    fn color_key(
+        &mut self,
+        size: usize,
+        mut kinds: [PlayerKind; 2],
+        item: usize,
+        key: Key,
+    ) -> Result<Next<'a>> {
+        if let Some(item) = select(key, item, 2, Layout::Horizontal) {
+            return Ok(Next::Redraw(Screen::Color { size, kinds, item }));
+        }
+        if key != Key::Enter {
+            return Ok(Next::Ignore(Screen::Color { size, kinds, item }));
+        }
+        if item == 1 {
+            kinds.swap(0, 1);
+        }
+        self.start(size, kinds)
+    }
+
+    /// 新しい盤面で対局を始める
+    fn start(&mut self, size: usize, kinds: [PlayerKind; 2]) -> Result<Next<'a>> {
+        let bs = BoardState::new(size / 2, false);
+        let m = self.new_match(kinds, bs, None)?;
+        Ok(Next::Redraw(Screen::Playing(Box::new(m))))
+    }
+
+    /// 盤面から対局を始める（人間の指し手はこの画面に描いてキー入力を読む）
+    fn new_match(
+        &self,
+        kinds: [PlayerKind; 2],
+        bs: BoardState,
+        connection: Option<Connection>,
+    ) -> io::Result<Match<'a>> {
+        Match::new(kinds, bs, connection, self.seed, &self.out, &self.input)
+    }
+
+    /// 対局中の画面
+    ///
+    /// 人間の番の盤面と項目は、キー入力を読む前に人間の指し手が描く。
+    /// 描くたびに観戦者にも今の局面を送る（待ったなどで局面が戻った場合も含む）。
+    fn draw_playing(&mut self, m: &Match<'a>, full: bool) -> Result<()> {
+        if let Some(server) = &self.spectators {
+            server.broadcast(&m.bs);
+        }
+        let size = m.size;
+        let human = m.turn_is_human();
+        if full {
+            self.draw_header(Some(size), Some(m.kinds))?;
+            let help = if !human {
+                "Escキーで終了．"
+            } else if m.connection.is_some() {
+                "↑↓←→キーで選択，Enterキーで決定，Escキーで終了．"
+            } else {
+                "↑↓←→キーで選択，Enterキーで決定，uキーで待った，rキーでやり直し，sキーで保存，Escキーで終了．"
+            };
+            self.out
+                .print(0, 3, &(preview_turn(&m.bs) + help), Style::plain())?;
+
+            // 「そこには置けません」や保存した結果のメッセージの表示
+            if m.not_puttable {
+                self.out.print(
+                    0,
+                    6 + size as u16,
+                    "そこには置けません",
+                    Style::bold().with_fg(Color::Red),
+                )?;
+            }
+            if let Some(message) = &m.save_message {
+                self.out.print(0, 6 + size as u16, message, Style::bold())?;
+            }
+
+            // パスのお知らせ
+            if let Some(c) = m.passed_piece {
+                self.out.print(
+                    0,
+                    7 + size as u16,
+                    &format!("{}は置ける場所がないのでパスです．", c),
+                    Style::bold(),
+                )?;
+            }
+        }
+
+        // CPUや通信相手の番の場合はカーソルを出さずに盤面を表示する
+        if !human {
+            preview_board(&mut self.out, &m.bs, size, size, BOARD_ROW)?;
+            if m.turn_kind() == PlayerKind::Remote {
+                self.out.print(
+                    0,
+                    5 + size as u16,
+                    "相手の手を待っています...",
+                    Style::bold(),
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 手番の側の指し手に手を決めさせて進める
+    ///
+    /// CPUの番は時間を空けつつメッセージを表示してから手を決めさせる。
+    /// 待っている間も終了するキーを受け付ける（人間の番は人間の指し手が読む）。
+    fn play_turn(&mut self, mut m: Box<Match<'a>>) -> Result<Next<'a>> {
+        let side = side_of(&m.bs);
+        let remote = m.kinds[side] == PlayerKind::Remote;
+        let human = m.turn_is_human();
+        if !remote && !human {
+            let cpu_only = !m.kinds[0].is_human() && !m.kinds[1].is_human();
+            if !cpu_only && self.input.borrow_mut().wait(Duration::from_millis(250))? {
+                return Ok(Next::Exit);
+            }
+            self.out
+                .print(0, 5 + m.size as u16, "CPU操作中...", Style::bold())?;
+            self.out.flush()?;
+            if self
+                .input
+                .borrow_mut()
+                .wait(Duration::from_millis(if cpu_only { 500 } else { 750 }))?
+            {
+                return Ok(Next::Exit);
+            }
+        }
+
+        let player = m.players[side].as_mut().expect("対局中は指し手がいる");
+        let bs = &m.bs;
+        let result = match &m.connection {
+            // 通信相手の手を待つ間は別のスレッドで終了するキーを見張る
+            Some(conn) if remote => {
+                let connection = conn.try_clone()?;
+                let mut input = self.input.borrow_mut();
+                let input = &mut **input;
+                let stop = AtomicBool::new(false);
+                let (result, quit) = thread::scope(|scope| {
+                    let watcher = scope.spawn(|| watch_quit_keys(input, connection, &stop));
+                    let result = player.play(bs);
+                    stop.store(true, Ordering::SeqCst);
+                    (result, watcher.join().unwrap_or(false))
+                });
+                if quit {
+                    return Ok(Next::Exit);
+                }
+                result
+            }
+            _ => player.play(bs),
+        };
+
+        // 通信相手との接続が切れたなどの場合はゲームを終了
+        match result {
+            Ok(action) => self.apply(m, action, remote),
+            Err(e) if remote => {
+                m.net_message = Some(format!("{}．", e));
+                Ok(self.finish(m))
+            }
+            // 人間が終了するキーを押した
+            Err(e) if human && e.kind() == io::ErrorKind::Interrupted => {
+                m.send_quit();
+                Ok(Next::Exit)
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// 手番の側が選んだ行動を盤面に反映する（remoteは通信相手の手かどうか）
+    fn apply(&mut self, mut m: Box<Match<'a>>, action: Action, remote: bool) -> Result<Next<'a>> {
+        m.not_puttable = false;
+        m.save_message = None;
+        match action {
+            // 通信対戦では待った，やり直し，保存はできない
+            Action::Undo | Action::Redo | Action::Save if m.connection.is_some() => {
+                m.save_message = Some("通信対戦では使えません．".to_string());
+            }
+
+            // マス目更新（置けないマス目ならメッセージを出してやり直し）
+            Action::Put(pos) => match m.bs.try_put(pos.x, pos.y) {
+                Ok(outcome) => {
+                    // 通信対戦ではこちらの手を相手に送る
+                    if let Some(conn) = m.connection.as_mut().filter(|_| !remote) {
+                        if let Err(e) = conn.send(Message::Move(pos)) {
+                            m.net_message = Some(format!("{}．", e));
+                            return Ok(self.finish(m));
+                        }
+                    }
+                    // 続行できないときはゲームを終了
+                    if outcome.game_over {
+                        return Ok(self.finish(m));
+                    }
+                    m.passed_piece = passed_piece_of(&outcome);
+                }
+                Err(_) => m.not_puttable = true,
+            },
+
+            // 待った（人間の番まで戻す）
+            Action::Undo => {
+                while m.bs.undo().is_some() {
+                    if m.turn_is_human() {
+                        break;
+                    }
+                }
+                m.passed_piece = None;
+            }
+
+            // 待ったで取り消した手をやり直す（人間の番まで進める）
+            Action::Redo => {
+                while let Some(outcome) = m.bs.redo() {
+                    m.passed_piece = passed_piece_of(&outcome);
+                    if m.turn_is_human() || outcome.game_over {
+                        break;
+                    }
+                }
+                // やり直しでゲームの最後まで進んだ場合はゲームを終了
+                if m.bs.is_game_over() {
+                    return Ok(self.finish(m));
+                }
+            }
+
+            // 今の盤面と両者の指し手の種類を保存する
+            Action::Save => {
+                let saved = SavedGame::new(&m.bs, m.kinds[0], m.kinds[1]);
+                m.save_message = Some(match saved.save(&self.save_file) {
+                    Ok(()) => format!("{}に保存しました．", self.save_file.display()),
+                    Err(e) => format!("保存できませんでした：{}．", e),
+                });
+            }
+
+            Action::Quit => return Ok(Next::Redraw(Screen::ConfirmQuit(m, 0))),
+        }
+        Ok(Next::Redraw(Screen::Playing(m)))
+    }
+
+    /// 対局を終えて結果の画面に移る
+    ///
+    /// 結果の画面では通信しないので、最後の局面を観戦者に送ってから接続を閉じる。
+    fn finish(&mut self, mut m: Box<Match<'a>>) -> Next<'a> {
+        if let Some(server) = self.spectators.take() {
+            server.broadcast(&m.bs);
+        }
+        m.players = [None, None];
+        m.connection = None;
+        Next::Redraw(Screen::Result(m, None))
+    }
+
+    /// ゲームを終わるかどうかを確かめる画面
+    fn draw_confirm_quit(&mut self, m: &Match<'a>, item: usize) -> Result<()> {
+        self.draw_header(Some(m.size), Some(m.kinds))?;
+        self.out
+            .print(0, 5, "本当に終了しますか？", Style::bold())?;
+        draw_choices(
+            &mut self.out,
+            &[(2, 7, "はい".to_string()), (10, 7, "いいえ".to_string())],
+            item,
+        )
+    }
+
+    /// 「はい」なら結果の画面に、「いいえ」なら対局に戻る
+    fn confirm_quit_key(&mut self, mut m: Box<Match<'a>>, item: usize, key: Key) -> Next<'a> {
+        if let Some(item) = select(key, item, 2, Layout::Horizontal) {
+            return Next::Redraw(Screen::ConfirmQuit(m, item));
+        }
+        match key {
+            Key::Enter if item == 0 => {
+                m.send_quit();
+                self.finish(m)
+            }
+            Key::Enter => Next::Redraw(Screen::Playing(m)),
+            _ => Next::Ignore(Screen::ConfirmQuit(m, item)),
+        }
+    }
+
+    /// 結果の画面（fullがfalseなら棋譜を保存した結果のメッセージだけ描き直す）
+    fn draw_result(&mut self, m: &Match<'a>, message: Option<&str>, full: bool) -> Result<()> {
+        if full {
+            draw_result(
+                &mut self.out,
+                &m.bs,
+                m.kinds,
+                self.seed,
+                m.net_message.as_deref(),
+            )?;
+        }
+        if let Some(message) = message {
+            let row = 8 + m.size as u16;
+            self.out.clear_line(row)?;
+            self.out.print(0, row, message, Style::bold())?;
+        }
+        Ok(())
+    }
+}
+
+/// 数字を打って盤面のサイズを入力し、Enterキーで確定したらモードを選ぶ画面に進む
+fn size_key<'a>(
+    notice: Option<String>,
+    mut input: String,
+    mut error: Option<&'static str>,
+    key: Key,
+) -> Next<'a> {
+    match key {
+        Key::Char(c) => {
+            input.push(c);
+            error = None;
+        }
+        Key::Backspace => {
+            input.pop();
+        }
+        Key::Enter => {
+            match input.trim().parse::<usize>() {
+                Ok(size) if size >= 4 && size.is_multiple_of(2) => {
+                    return Next::Redraw(Screen::Mode {
+                        size,
+                        item: 0,
+                        level: DEFAULT_LEVEL,
+                    })
+                }
+                Ok(_) => error = Some(err_input()),
+                Err(_) => error = Some(err_not_int()),
+            }
+            input.clear();
+        }
+        _ => {
+            return Next::Ignore(Screen::Size {
+                notice,
+                input,
+                error,
+            })
+        }
+    }
+    Next::Redraw(Screen::Size {
+        notice,
+        input,
+        error,
+    })
+}
+
+/// 結果の画面ではEnterキーで終わり、gキーで棋譜をGGF形式でggf_fileの末尾に書き足す
+fn result_key<'a>(
+    m: Box<Match<'a>>,
+    message: Option<String>,
+    key: Key,
+    ggf_file: &Path,
+) -> Next<'a> {
+    match key {
+        Key::Enter => Next::Exit,
+        Key::Char('g') => {
+            let game = GgfGame::from_board(&m.bs, ggf_name(m.kinds[0]), ggf_name(m.kinds[1]));
+            let message = match append_line(ggf_file, &game.to_string()) {
+                Ok(()) => format!("{}に棋譜を保存しました．", ggf_file.display()),
+                Err(e) => format!("棋譜を保存できませんでした：{}．", e),
+            };
+            Next::Refresh(Screen::Result(m, Some(message)))
+        }
+        _ => Next::Ignore(Screen::Result(m, message)),
+    }
+}
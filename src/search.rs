@@ -0,0 +1,368 @@
+use crate::boardstate::{BoardState, Turn};
+use crate::endgame;
+use crate::moves::Position;
+
+/// 探索で使う無限大の代わり
+const INFINITY: i32 = i32::MAX / 2;
+
+/// 勝ちが確定した局面の点数（これに石差を足す）
+const WIN_SCORE: i32 = 1_000_000;
+
+/// 評価関数の重み：置けるマス目の数の差
+const WEIGHT_MOBILITY: i32 = 8;
+
+/// 評価関数の重み：隅の駒の数の差
+const WEIGHT_CORNER: i32 = 100;
+
+/// 評価関数の重み：確定石（もう裏返されない駒）の数の差
+const WEIGHT_STABLE: i32 = 20;
+
+/// 評価関数の重み：駒の数の差
+const WEIGHT_DISC: i32 = 1;
+
+/// 探索の打ち切り条件
+///
+/// depthは読む手数の上限、nodesは調べる局面の数の上限（```None```なら制限なし）。
+/// 1手読みは必ず最後まで調べるので、nodesが小さすぎても着手は必ず決まる。
+/// endgame_emptiesは終盤の読み切りに切り替える空きマスの数で、
+/// 空きマスがこれ以下になると評価関数を使わずに終局まで読み切る（0なら読み切らない）。
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct SearchLimits {
+    pub depth: usize,
+    pub nodes: Option<u64>,
+    pub endgame_empties: usize,
+}
+
+/// 探索の結果
+///
+/// best_moveが選んだ手（置ける手がない場合は```None```）、scoreがその手の評価値（手番の側から見た値）、
+/// depthが最後まで読み切れた深さ、nodesが調べた局面の数。
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct SearchResult {
+    pub best_move: Option<Position>,
+    pub score: i32,
+    pub depth: usize,
+    pub nodes: u64,
+}
+
+/// CPUの強さ
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Level {
+    Weak,
+    Normal,
+    Strong,
+}
+
+impl Level {
+    /// 弱い順に並べたもの
+    pub const ALL: [Level; 3] = [Level::Weak, Level::Normal, Level::Strong];
+
+    /// 強さに応じた探索の打ち切り条件
+    ///
+    /// 大きな盤面でも待たされすぎないように、局面の数でも打ち切る。
+    pub fn limits(self) -> SearchLimits {
+        match self {
+            Level::Weak => SearchLimits {
+                depth: 2,
+                nodes: Some(2_000),
+                endgame_empties: 0,
+            },
+            Level::Normal => SearchLimits {
+                depth: 4,
+                nodes: Some(50_000),
+                endgame_empties: 8,
+            },
+            Level::Strong => SearchLimits {
+                depth: 6,
+                nodes: Some(500_000),
+                endgame_empties: 12,
+            },
+        }
+    }
+
+    /// 画面に表示する名前
+    pub fn name(self) -> &'static str {
+        match self {
+            Level::Weak => "弱い",
+            Level::Normal => "普通",
+            Level::Strong => "強い",
+        }
+    }
+}
+
+/// 局面の評価値（playerの側から見て大きいほど良い）
+///
+/// 置けるマス目の数、隅、確定石、駒の数の差に重みをつけて足し合わせる。
+pub fn evaluate(bs: &BoardState, player: Turn) -> i32 {
+    let opponent = player.opponent();
+    let mobility =
+        bs.legal_moves_for(player).len() as i32 - bs.legal_moves_for(opponent).len() as i32;
+    let corner = count_corners(bs, player) - count_corners(bs, opponent);
+    let stable = count_stable(bs, player) - count_stable(bs, opponent);
+    let disc = disc_difference(bs, player);
+    WEIGHT_MOBILITY * mobility
+        + WEIGHT_CORNER * corner
+        + WEIGHT_STABLE * stable
+        + WEIGHT_DISC * disc
+}
+
+/// playerの駒の数から相手の駒の数を引いたもの
+pub fn disc_difference(bs: &BoardState, player: Turn) -> i32 {
+    let ((_, white), (_, black)) = bs.count_pieces();
+    let diff = white as i32 - black as i32;
+    match player {
+        Turn::White => diff,
+        Turn::Black => -diff,
+    }
+}
+
+/// 盤面の四隅
+fn corners(n: usize) -> [Position; 4] {
+    [
+        Position::new(0, 0),
+        Position::new(0, n - 1),
+        Position::new(n - 1, 0),
+        Position::new(n - 1, n - 1),
+    ]
+}
+
+/// playerの隅の駒の数
+fn count_corners(bs: &BoardState, player: Turn) -> i32 {
+    corners(bs.get_size())
+        .iter()
+        .filter(|&&c| bs.get(c) == Some(player))
+        .count() as i32
+}
+
+/// playerの確定石の数（の見積もり）
+///
+/// 自分の隅から辺に沿って自分の駒が途切れずに続いている部分だけを数える。
+fn count_stable(bs: &BoardState, player: Turn) -> i32 {
+    let n = bs.get_size();
+    let mut stable = vec![vec![false; n]; n];
+    for &c in corners(n).iter() {
+        if bs.get(c) != Some(player) {
+            continue;
+        }
+        // 隅から縦と横の2方向に進む
+        let sx: i32 = if c.x == 0 { 1 } else { -1 };
+        let sy: i32 = if c.y == 0 { 1 } else { -1 };
+        for &(dx, dy) in [(sx, 0), (0, sy)].iter() {
+            for l in 0..n as i32 {
+                let p = Position::new(
+                    (c.x as i32 + l * dx) as usize,
+                    (c.y as i32 + l * dy) as usize,
+                );
+                if bs.get(p) != Some(player) {
+                    break;
+                }
+                stable[p.x][p.y] = true;
+            }
+        }
+    }
+    stable.iter().flatten().filter(|&&b| b).count() as i32
+}
+
+/// 手を調べる順番の目安（大きいほど先に調べる）
+///
+/// 隅を先に、隅の斜め隣（相手に隅を取られやすいマス目）を後に調べると枝刈りがよく効く。
+fn move_priority(pos: Position, n: usize) -> i32 {
+    let edge_x = pos.x == 0 || pos.x == n - 1;
+    let edge_y = pos.y == 0 || pos.y == n - 1;
+    let near_x = pos.x == 1 || pos.x == n - 2;
+    let near_y = pos.y == 1 || pos.y == n - 2;
+    if edge_x && edge_y {
+        3
+    } else if near_x && near_y {
+        -1
+    } else if edge_x || edge_y {
+        1
+    } else {
+        0
+    }
+}
+
+/// 調べる順番に並べた置けるマス目
+fn ordered_moves(bs: &BoardState) -> Vec<Position> {
+    let n = bs.get_size();
+    let mut moves = bs.legal_moves();
+    moves.sort_by_key(|&p| -move_priority(p, n));
+    moves
+}
+
+/// 終局した局面の点数（手番の側から見た値）
+fn final_score(bs: &BoardState) -> i32 {
+    score_of_disc_difference(disc_difference(bs, bs.turn()))
+}
+
+/// 探索の点数を石差の見込みに直す（手番の側から見た値）
+///
+/// 終局まで読み切った点数はそのときの石差にする。
+/// 評価関数の点数は隅1つを10石くらいとみなして換算した目安。
+pub fn score_to_discs(score: i32) -> f64 {
+    if score >= WIN_SCORE / 2 {
+        (score - WIN_SCORE) as f64
+    } else if score <= -WIN_SCORE / 2 {
+        (score + WIN_SCORE) as f64
+    } else {
+        score as f64 * 10.0 / WEIGHT_CORNER as f64
+    }
+}
+
+/// 終局時の石差を探索の点数に直す
+fn score_of_disc_difference(diff: i32) -> i32 {
+    if diff > 0 {
+        WIN_SCORE + diff
+    } else if diff < 0 {
+        -WIN_SCORE + diff
+    } else {
+        0
+    }
+}
+
+/// 探索中の状態
+///
+/// nodesは調べた局面の数、node_limitはその上限。
+/// can_abortがtrueのときに上限を超えるとabortedをtrueにして探索を打ち切る。
+struct Searcher {
+    nodes: u64,
+    node_limit: Option<u64>,
+    can_abort: bool,
+    aborted: bool,
+}
+
+impl Searcher {
+    /// posに置いた後の局面を読んで、手番の側（置く側）から見た点数を返す
+    fn score_move(
+        &mut self,
+        bs: &mut BoardState,
+        pos: Position,
+        depth: usize,
+        alpha: i32,
+        beta: i32,
+    ) -> i32 {
+        let me = bs.turn();
+        bs.apply(pos.x, pos.y);
+        // 相手がパスになった（または終局した）ときは手番が自分のままなので符号を反転しない
+        let score = if bs.turn() == me {
+            self.negamax(bs, depth - 1, alpha, beta)
+        } else {
+            -self.negamax(bs, depth - 1, -beta, -alpha)
+        };
+        bs.take_back();
+        score
+    }
+
+    /// アルファベータ法（ネガマックス法）で局面を読む
+    ///
+    /// 返り値は手番の側から見た点数。
+    fn negamax(&mut self, bs: &mut BoardState, depth: usize, mut alpha: i32, beta: i32) -> i32 {
+        self.nodes += 1;
+        if let Some(limit) = self.node_limit {
+            if self.can_abort && self.nodes > limit {
+                self.aborted = true;
+                return 0;
+            }
+        }
+
+        let moves = ordered_moves(bs);
+        if moves.is_empty() {
+            if bs.is_game_over() {
+                return final_score(bs);
+            }
+            // 手番の側だけが置けない局面ではパスして相手の番を読む
+            bs.switch_turn();
+            let score = -self.negamax(bs, depth, -beta, -alpha);
+            bs.switch_turn();
+            return score;
+        }
+        if depth == 0 {
+            return evaluate(bs, bs.turn());
+        }
+
+        let mut best = -INFINITY;
+        for pos in moves {
+            let score = self.score_move(bs, pos, depth, alpha, beta);
+            if self.aborted {
+                return 0;
+            }
+            best = best.max(score);
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+        best
+    }
+}
+
+/// アルファベータ法で手番の側の最善手を探す
+///
+/// 深さ1から順に深くしていき（反復深化）、打ち切り条件に達したら
+/// 最後まで読み切れた深さでの最善手を返す。
+/// 空きマスが```limits.endgame_empties```以下なら```endgame::solve```で終局まで読み切る。
+pub fn search(bs: &BoardState, limits: SearchLimits) -> SearchResult {
+    let empties = bs.count_empties();
+    if limits.endgame_empties > 0 && empties <= limits.endgame_empties {
+        let solved = endgame::solve(bs);
+        return SearchResult {
+            best_move: solved.best_move,
+            score: score_of_disc_difference(solved.disc_diff),
+            depth: empties,
+            nodes: solved.nodes,
+        };
+    }
+
+    let mut board = bs.clone();
+    let mut moves = ordered_moves(&board);
+    let mut result = SearchResult {
+        best_move: moves.first().copied(),
+        score: 0,
+        depth: 0,
+        nodes: 0,
+    };
+    if moves.is_empty() {
+        return result;
+    }
+
+    let mut searcher = Searcher {
+        nodes: 0,
+        node_limit: limits.nodes,
+        can_abort: false,
+        aborted: false,
+    };
+    for depth in 1..=limits.depth.max(1) {
+        searcher.can_abort = depth > 1;
+        let mut best_move = moves[0];
+        let mut best_score = -INFINITY;
+        let mut alpha = -INFINITY;
+        for &pos in &moves {
+            let score = searcher.score_move(&mut board, pos, depth, alpha, INFINITY);
+            if searcher.aborted {
+                break;
+            }
+            if score > best_score {
+                best_move = pos;
+                best_score = score;
+            }
+            alpha = alpha.max(score);
+        }
+        if searcher.aborted {
+            break;
+        }
+        result.best_move = Some(best_move);
+        result.score = best_score;
+        result.depth = depth;
+
+        // 次の深さでは今回の最善手から調べる
+        moves.retain(|&p| p != best_move);
+        moves.insert(0, best_move);
+
+        // 勝ち負けが読み切れたらそれ以上深く読まない
+        if best_score.abs() >= WIN_SCORE {
+            break;
+        }
+    }
+    result.nodes = searcher.nodes;
+    result
+}
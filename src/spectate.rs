@@ -0,0 +1,230 @@
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crate::boardstate::{BoardState, ParseBoardError};
+use crate::moves::{Move, Position};
+use crate::notation::NotationError;
+
+/// 観戦者への送信を待つ時間の上限（読まない観戦者のせいで対局が止まらないように）
+const WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// 観戦者に送る1手ごとの局面
+///
+/// boardは局面（履歴は含まない）、last_moveは直前に置かれたマス目（まだ1手も打たれていなければ```None```）。
+/// 1行の文字列として```..../.o#./.##./...# o d4```のように局面の文字列と直前の手を空白で区切って送る
+/// （まだ1手も打たれていなければ直前の手は```-```）。
+#[derive(Clone, Debug)]
+pub struct Update {
+    pub board: BoardState,
+    pub last_move: Option<Position>,
+}
+
+impl Update {
+    /// 盤面から送る局面を作る（直前の手は履歴の最後に置かれた手）
+    pub fn new(bs: &BoardState) -> Update {
+        let last_move = bs.history().iter().rev().find_map(|entry| match entry.mv {
+            Move::Put(pos) => Some(pos),
+            Move::Pass => None,
+        });
+        Update {
+            board: bs.without_history(),
+            last_move,
+        }
+    }
+}
+
+impl fmt::Display for Update {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.last_move {
+            Some(pos) => write!(f, "{} {}", self.board, pos),
+            None => write!(f, "{} -", self.board),
+        }
+    }
+}
+
+impl FromStr for Update {
+    type Err = SpectateError;
+
+    fn from_str(s: &str) -> Result<Update, SpectateError> {
+        let s = s.trim();
+        let k = s
+            .rfind(' ')
+            .ok_or_else(|| SpectateError::Format(s.to_string()))?;
+        let board = s[..k].parse().map_err(SpectateError::Board)?;
+        let last_move = match &s[k + 1..] {
+            "-" => None,
+            square => Some(square.parse().map_err(SpectateError::LastMove)?),
+        };
+        Ok(Update { board, last_move })
+    }
+}
+
+/// 観戦できなかった理由
+#[derive(Debug)]
+pub enum SpectateError {
+    /// 接続や受信に失敗した
+    Io(io::Error),
+    /// 局面と直前の手に分けられない行が届いた
+    Format(String),
+    /// 局面として読めなかった
+    Board(ParseBoardError),
+    /// 直前の手として読めなかった
+    LastMove(NotationError),
+}
+
+impl fmt::Display for SpectateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpectateError::Io(e) => write!(f, "通信に失敗しました（{}）", e),
+            SpectateError::Format(s) => write!(f, "局面として読めない行が届きました：{}", s),
+            SpectateError::Board(e) => write!(f, "局面として読めませんでした（{}）", e),
+            SpectateError::LastMove(e) => write!(f, "直前の手として読めませんでした（{}）", e),
+        }
+    }
+}
+
+impl Error for SpectateError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SpectateError::Io(e) => Some(e),
+            SpectateError::Board(e) => Some(e),
+            SpectateError::LastMove(e) => Some(e),
+            SpectateError::Format(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for SpectateError {
+    fn from(e: io::Error) -> SpectateError {
+        SpectateError::Io(e)
+    }
+}
+
+/// 配信している側と受け付け用のスレッドで共有するもの
+///
+/// clientsは接続中の観戦者、latestは最後に送った行（途中から来た観戦者にも送る）、
+/// closedは配信をやめたかどうか。
+#[derive(Debug, Default)]
+struct Shared {
+    clients: Vec<TcpStream>,
+    latest: Option<String>,
+    closed: bool,
+}
+
+/// 観戦者に局面を配信するサーバー
+///
+/// 観戦者の接続は別のスレッドで受け付け、来たらすぐに今の局面を送る。
+/// 送れなくなった観戦者は外すだけで、対局には影響しない。
+/// 捨てると観戦者との接続をすべて閉じる。
+#[derive(Debug)]
+pub struct SpectatorServer {
+    local_addr: SocketAddr,
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl SpectatorServer {
+    /// addrで観戦者の接続を待ち受け始める
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> io::Result<SpectatorServer> {
+        let listener = TcpListener::bind(addr)?;
+        let local_addr = listener.local_addr()?;
+        let shared = Arc::new(Mutex::new(Shared::default()));
+        let accepting = Arc::clone(&shared);
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut shared = accepting.lock().unwrap();
+                if shared.closed {
+                    break;
+                }
+                let mut stream = match stream {
+                    Ok(stream) => stream,
+                    Err(_) => continue,
+                };
+                if stream.set_write_timeout(Some(WRITE_TIMEOUT)).is_err() {
+                    continue;
+                }
+                if let Some(line) = &shared.latest {
+                    if writeln!(stream, "{}", line).is_err() {
+                        continue;
+                    }
+                }
+                shared.clients.push(stream);
+            }
+        });
+        Ok(SpectatorServer { local_addr, shared })
+    }
+
+    /// 待ち受けているアドレス
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// 接続中の観戦者の数
+    pub fn spectators(&self) -> usize {
+        self.shared.lock().unwrap().clients.len()
+    }
+
+    /// 今の局面を観戦者全員に送る（前に送った局面と同じなら送らない）
+    pub fn broadcast(&self, bs: &BoardState) {
+        let line = Update::new(bs).to_string();
+        let mut shared = self.shared.lock().unwrap();
+        if shared.latest.as_ref() == Some(&line) {
+            return;
+        }
+        shared
+            .clients
+            .retain(|mut client| writeln!(client, "{}", line).is_ok());
+        shared.latest = Some(line);
+    }
+}
+
+impl Drop for SpectatorServer {
+    fn drop(&mut self) {
+        {
+            let mut shared = self.shared.lock().unwrap();
+            shared.closed = true;
+            shared.clients.clear();
+        }
+        // 受け付け用のスレッドが接続を待ったままにならないように、自分に接続して起こす
+        let mut wake = self.local_addr;
+        if wake.ip().is_unspecified() {
+            wake.set_ip([127, 0, 0, 1].into());
+        }
+        TcpStream::connect(wake).ok();
+    }
+}
+
+/// 配信しているサーバーに接続して局面を受け取る観戦者
+///
+/// ```Iterator```として局面を1つずつ返し、配信が終わると（接続が切れると）終わる。
+#[derive(Debug)]
+pub struct SpectatorClient {
+    reader: BufReader<TcpStream>,
+}
+
+impl SpectatorClient {
+    /// 配信しているサーバーに接続する
+    pub fn connect<A: ToSocketAddrs>(addr: A) -> io::Result<SpectatorClient> {
+        Ok(SpectatorClient {
+            reader: BufReader::new(TcpStream::connect(addr)?),
+        })
+    }
+}
+
+impl Iterator for SpectatorClient {
+    type Item = Result<Update, SpectateError>;
+
+    fn next(&mut self) -> Option<Result<Update, SpectateError>> {
+        let mut line = String::new();
+        match self.reader.read_line(&mut line) {
+            Ok(0) => None,
+            Ok(_) => Some(line.parse()),
+            Err(e) => Some(Err(SpectateError::Io(e))),
+        }
+    }
+}
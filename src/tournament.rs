@@ -0,0 +1,252 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::time::{Duration, Instant};
+
+use crate::boardstate::{BoardState, Turn};
+use crate::moves::{MoveError, Position};
+use crate::player::{Action, Player, PlayerKind, RandomPlayer, SearchPlayer};
+use crate::search::{disc_difference, Level};
+
+/// 画面なしで対局させられないなど、連続対局を続けられなかった理由
+#[derive(Debug)]
+pub enum TournamentError {
+    /// CPUでない指し手が指定された
+    NotCpu(PlayerKind),
+    /// 指し手が行動を決められなかった
+    Io(io::Error),
+    /// 指し手が打てない手を返した（何局目かは1始まり）
+    IllegalMove(usize, Position, MoveError),
+    /// 指し手が置く手以外の行動を返した（何局目かは1始まり）
+    UnexpectedAction(usize, Action),
+}
+
+impl fmt::Display for TournamentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TournamentError::NotCpu(kind) => {
+                write!(f, "{:?}はCPUでないので連続対局に使えません", kind)
+            }
+            TournamentError::Io(e) => write!(f, "指し手が手を決められませんでした（{}）", e),
+            TournamentError::IllegalMove(game, pos, e) => {
+                write!(f, "{}局目で打てない手{}が選ばれました（{}）", game, pos, e)
+            }
+            TournamentError::UnexpectedAction(game, action) => {
+                write!(
+                    f,
+                    "{}局目で置く手以外の行動{:?}が選ばれました",
+                    game, action
+                )
+            }
+        }
+    }
+}
+
+impl Error for TournamentError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            TournamentError::Io(e) => Some(e),
+            TournamentError::IllegalMove(_, _, e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for TournamentError {
+    fn from(e: io::Error) -> TournamentError {
+        TournamentError::Io(e)
+    }
+}
+
+/// CPUの名前（```random```、```weak```、```normal```、```strong```）から指し手の種類を読む
+pub fn cpu_kind(name: &str) -> Option<PlayerKind> {
+    match name {
+        "random" => Some(PlayerKind::Random),
+        "weak" => Some(PlayerKind::Search(Level::Weak)),
+        "normal" => Some(PlayerKind::Search(Level::Normal)),
+        "strong" => Some(PlayerKind::Search(Level::Strong)),
+        _ => None,
+    }
+}
+
+/// 指し手の種類からCPUを作る（ランダムなCPUはシードから乱数を作る）
+fn make_cpu(kind: PlayerKind, seed: u64) -> Result<Box<dyn Player>, TournamentError> {
+    match kind {
+        PlayerKind::Random => Ok(Box::new(RandomPlayer::from_seed(seed))),
+        PlayerKind::Search(level) => Ok(Box::new(SearchPlayer::with_level(level))),
+        PlayerKind::Human | PlayerKind::Remote => Err(TournamentError::NotCpu(kind)),
+    }
+}
+
+/// 指し手1人分の着手の記録
+///
+/// movesは打った手の数、timeは手を決めるのにかかった時間の合計、max_timeはそのうち最も長かったもの。
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct MoveTimes {
+    pub moves: u64,
+    pub time: Duration,
+    pub max_time: Duration,
+}
+
+impl MoveTimes {
+    /// 1手を記録する
+    fn record(&mut self, elapsed: Duration) {
+        self.moves += 1;
+        self.time += elapsed;
+        self.max_time = self.max_time.max(elapsed);
+    }
+
+    /// 1手あたりの平均時間（1手も打っていなければ0）
+    pub fn average(&self) -> Duration {
+        if self.moves == 0 {
+            Duration::from_secs(0)
+        } else {
+            self.time / self.moves as u32
+        }
+    }
+}
+
+/// 対局1つ分の結果
+///
+/// first_is_blackは1人目の指し手が黒だったかどうか、disc_diffは1人目から見た終局時の石差、
+/// boardは終局した盤面（履歴つき）。
+#[derive(Clone, Debug)]
+pub struct GameOutcome {
+    pub first_is_black: bool,
+    pub disc_diff: i32,
+    pub board: BoardState,
+}
+
+/// 連続対局の成績（どれも1人目の指し手から見たもの）
+///
+/// timesは1人目と2人目の着手の記録。
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct TournamentStats {
+    pub games: usize,
+    pub wins: usize,
+    pub losses: usize,
+    pub draws: usize,
+    pub disc_diff_total: i64,
+    pub times: [MoveTimes; 2],
+}
+
+impl TournamentStats {
+    /// 1局あたりの平均の石差（1局もしていなければ0）
+    pub fn average_disc_diff(&self) -> f64 {
+        if self.games == 0 {
+            0.0
+        } else {
+            self.disc_diff_total as f64 / self.games as f64
+        }
+    }
+
+    /// 引き分けを半分の勝ちとして数えた勝率（1局もしていなければ0）
+    pub fn score_rate(&self) -> f64 {
+        if self.games == 0 {
+            0.0
+        } else {
+            (self.wins as f64 + self.draws as f64 / 2.0) / self.games as f64
+        }
+    }
+}
+
+/// 2人のCPUを画面なしで続けて対局させる設定
+///
+/// sizeは盤面のサイズ、gamesは対局の数、playersは対局させる2人、seedはランダムなCPUの乱数のシード。
+/// 先手（黒）は1局ごとに入れ替え、1局目は1人目が黒。
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Tournament {
+    pub size: usize,
+    pub games: usize,
+    pub players: [PlayerKind; 2],
+    pub seed: u64,
+}
+
+impl Tournament {
+    /// 全部の対局をして成績を返す（1局終わるごとに何局目か（0始まり）と結果をprogressに渡す）
+    pub fn run<F: FnMut(usize, &GameOutcome)>(
+        &self,
+        mut progress: F,
+    ) -> Result<TournamentStats, TournamentError> {
+        let mut stats = TournamentStats::default();
+        for game in 0..self.games {
+            let first_is_black = game % 2 == 0;
+            // 対局ごと、指し手ごとにシードをずらして同じ対局の繰り返しにならないようにする
+            let seed = self.seed.wrapping_add(2 * game as u64);
+            let mut first = make_cpu(self.players[0], seed)?;
+            let mut second = make_cpu(self.players[1], seed.wrapping_add(1))?;
+            let [first_times, second_times] = &mut stats.times;
+            let board = if first_is_black {
+                play_game(
+                    game + 1,
+                    self.size,
+                    [first.as_mut(), second.as_mut()],
+                    [first_times, second_times],
+                )?
+            } else {
+                play_game(
+                    game + 1,
+                    self.size,
+                    [second.as_mut(), first.as_mut()],
+                    [second_times, first_times],
+                )?
+            };
+
+            let first_color = if first_is_black {
+                Turn::Black
+            } else {
+                Turn::White
+            };
+            let disc_diff = disc_difference(&board, first_color);
+            stats.games += 1;
+            stats.disc_diff_total += disc_diff as i64;
+            if disc_diff > 0 {
+                stats.wins += 1;
+            } else if disc_diff < 0 {
+                stats.losses += 1;
+            } else {
+                stats.draws += 1;
+            }
+            progress(
+                game,
+                &GameOutcome {
+                    first_is_black,
+                    disc_diff,
+                    board,
+                },
+            );
+        }
+        Ok(stats)
+    }
+}
+
+/// 黒と白の指し手で1局打ち、終局した盤面を返す（gameは何局目かで、エラーに添える）
+///
+/// timesには黒と白それぞれの手を決めるのにかかった時間を足していく。
+pub fn play_game(
+    game: usize,
+    size: usize,
+    players: [&mut dyn Player; 2],
+    times: [&mut MoveTimes; 2],
+) -> Result<BoardState, TournamentError> {
+    let [black, white] = players;
+    let [black_times, white_times] = times;
+    let mut bs = BoardState::new(size / 2, false);
+    while !bs.is_game_over() {
+        let (player, times) = match bs.turn() {
+            Turn::Black => (&mut *black, &mut *black_times),
+            Turn::White => (&mut *white, &mut *white_times),
+        };
+        let start = Instant::now();
+        let action = player.play(&bs)?;
+        times.record(start.elapsed());
+        match action {
+            Action::Put(pos) => {
+                bs.try_put(pos.x, pos.y)
+                    .map_err(|e| TournamentError::IllegalMove(game, pos, e))?;
+            }
+            action => return Err(TournamentError::UnexpectedAction(game, action)),
+        }
+    }
+    Ok(bs)
+}
@@ -0,0 +1,344 @@
+use crate::boardstate::Turn;
+use crate::moves::Position;
+
+/// 任意のサイズの盤面用のビット列
+///
+/// マス目(x, y)が```n * x + y```番目のビットに対応し、64ビットごとにu64に詰める。
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    /// 空のビット列
+    fn empty(len: usize) -> BitSet {
+        BitSet {
+            words: vec![0; len.div_ceil(64)],
+        }
+    }
+
+    /// k番目のビットが立っているかどうか
+    fn contains(&self, k: usize) -> bool {
+        self.words[k / 64] & (1 << (k % 64)) != 0
+    }
+
+    /// k番目のビットを立てる
+    fn insert(&mut self, k: usize) {
+        self.words[k / 64] |= 1 << (k % 64);
+    }
+
+    /// k番目のビットを消す
+    fn remove(&mut self, k: usize) {
+        self.words[k / 64] &= !(1 << (k % 64));
+    }
+
+    /// 何もビットが立っていないかどうか
+    fn is_empty(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    /// 立っているビットの個数
+    fn count(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// 立っているビットの番号を小さい順に並べたもの
+    fn indices(&self) -> Vec<usize> {
+        let mut v: Vec<usize> = Vec::with_capacity(self.count());
+        for (i, &w) in self.words.iter().enumerate() {
+            let mut w = w;
+            while w != 0 {
+                v.push(64 * i + w.trailing_zeros() as usize);
+                w &= w - 1;
+            }
+        }
+        v
+    }
+
+    /// 和集合（selfを書き換える）
+    fn or_assign(&mut self, other: &BitSet) {
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a |= b;
+        }
+    }
+
+    /// 差集合（selfを書き換える）
+    fn remove_all(&mut self, other: &BitSet) {
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a &= !b;
+        }
+    }
+
+    /// dの方向にずらしてfilterとの共通部分を取ったものをoutに書き込む
+    ///
+    /// 呼ぶたびにビット列を作り直さないように、書き込み先は呼ぶ側で用意する。
+    fn shift_into(&self, d: &Direction, filter: &BitSet, out: &mut BitSet) {
+        let len = self.words.len();
+        let (ws, bs) = (d.step / 64, d.step % 64);
+        for i in 0..len {
+            let w = if d.up {
+                let mut w = if i >= ws { self.words[i - ws] << bs } else { 0 };
+                if bs > 0 && i > ws {
+                    w |= self.words[i - ws - 1] >> (64 - bs);
+                }
+                w
+            } else {
+                let mut w = if i + ws < len {
+                    self.words[i + ws] >> bs
+                } else {
+                    0
+                };
+                if bs > 0 && i + ws + 1 < len {
+                    w |= self.words[i + ws + 1] << (64 - bs);
+                }
+                w
+            };
+            out.words[i] = w & d.mask.words[i] & filter.words[i];
+        }
+    }
+}
+
+/// 1方向へのずらし方
+///
+/// stepはずらすビット数で、upがtrueなら番号が大きい側にずらす。
+/// maskはずらした後に残すマス目で、盤面の端をまたいで反対側に回り込んだビットと
+/// 盤面の外に出たビットを消すのに使う。
+/// dx, dyはその方向に1マス進んだときのx, yの変化。
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+struct Direction {
+    step: usize,
+    up: bool,
+    mask: BitSet,
+    dx: i32,
+    dy: i32,
+}
+
+/// 任意のサイズの盤面をビット列2つで表す構造体
+///
+/// sizeは盤面のサイズ（高さ=幅）。
+/// blackが黒い駒の置かれたマス目、whiteが白い駒の置かれたマス目。
+/// directionsは8方向へのずらし方で、盤面のサイズから作っておく。
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct WideBitboard {
+    size: usize,
+    black: BitSet,
+    white: BitSet,
+    board: BitSet,
+    directions: Vec<Direction>,
+}
+
+impl WideBitboard {
+    /// 何も置いていない盤面を作成する
+    pub fn empty(n: usize) -> WideBitboard {
+        assert!(n != 0);
+        let len = n * n;
+        let mut board = BitSet::empty(len);
+        let mut not_left = BitSet::empty(len);
+        let mut not_right = BitSet::empty(len);
+        for k in 0..len {
+            board.insert(k);
+            if k % n != 0 {
+                not_left.insert(k);
+            }
+            if k % n != n - 1 {
+                not_right.insert(k);
+            }
+        }
+        let direction = |dx: i32, dy: i32, mask: &BitSet| {
+            let d = dx * n as i32 + dy;
+            Direction {
+                step: d.unsigned_abs() as usize,
+                up: d > 0,
+                mask: mask.clone(),
+                dx,
+                dy,
+            }
+        };
+        let directions = vec![
+            direction(0, 1, &not_left),
+            direction(0, -1, &not_right),
+            direction(1, 0, &board),
+            direction(-1, 0, &board),
+            direction(1, 1, &not_left),
+            direction(1, -1, &not_right),
+            direction(-1, 1, &not_left),
+            direction(-1, -1, &not_right),
+        ];
+        WideBitboard {
+            size: n,
+            black: BitSet::empty(len),
+            white: BitSet::empty(len),
+            board,
+            directions,
+        }
+    }
+
+    /// 初期配置の盤面を作成する（nは偶数）
+    pub fn new(n: usize) -> WideBitboard {
+        assert!(n.is_multiple_of(2));
+        let mut b = WideBitboard::empty(n);
+        let h = n / 2;
+        b.set(h - 1, h - 1, Some(Turn::White));
+        b.set(h - 1, h, Some(Turn::Black));
+        b.set(h, h - 1, Some(Turn::Black));
+        b.set(h, h, Some(Turn::White));
+        b
+    }
+
+    /// 盤面の大きさ
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// マス目に対応するビットの番号
+    fn index(&self, x: usize, y: usize) -> usize {
+        self.size * x + y
+    }
+
+    /// ビットの番号に対応するマス目
+    fn position(&self, k: usize) -> Position {
+        Position::new(k / self.size, k % self.size)
+    }
+
+    /// turnの側の駒と相手の駒
+    fn sides(&self, turn: Turn) -> (&BitSet, &BitSet) {
+        match turn {
+            Turn::Black => (&self.black, &self.white),
+            Turn::White => (&self.white, &self.black),
+        }
+    }
+
+    /// 何も置いてないマス目
+    fn vacant(&self) -> BitSet {
+        let mut e = self.board.clone();
+        e.remove_all(&self.black);
+        e.remove_all(&self.white);
+        e
+    }
+
+    /// そのマス目に置かれている駒
+    pub fn get(&self, x: usize, y: usize) -> Option<Turn> {
+        let k = self.index(x, y);
+        if self.black.contains(k) {
+            Some(Turn::Black)
+        } else if self.white.contains(k) {
+            Some(Turn::White)
+        } else {
+            None
+        }
+    }
+
+    /// そのマス目の駒を置き換える
+    pub fn set(&mut self, x: usize, y: usize, piece: Option<Turn>) {
+        let k = self.index(x, y);
+        self.black.remove(k);
+        self.white.remove(k);
+        match piece {
+            Some(Turn::Black) => self.black.insert(k),
+            Some(Turn::White) => self.white.insert(k),
+            None => {}
+        }
+    }
+
+    /// 駒の個数（白、黒の順）
+    pub fn count(&self) -> (usize, usize) {
+        (self.white.count(), self.black.count())
+    }
+
+    /// turnの側が置けるマス目のビット列
+    fn legal_set(&self, turn: Turn) -> BitSet {
+        let (me, opp) = self.sides(turn);
+        let empty = self.vacant();
+        let len = self.size * self.size;
+        let mut moves = BitSet::empty(len);
+        let mut t = BitSet::empty(len);
+        let mut next = BitSet::empty(len);
+        for d in &self.directions {
+            // 自分の駒から相手の駒が続いている範囲を伸ばしていく
+            me.shift_into(d, opp, &mut t);
+            if t.is_empty() {
+                continue;
+            }
+            loop {
+                t.shift_into(d, opp, &mut next);
+                next.or_assign(&t);
+                if next == t {
+                    break;
+                }
+                std::mem::swap(&mut t, &mut next);
+            }
+            t.shift_into(d, &empty, &mut next);
+            moves.or_assign(&next);
+        }
+        moves
+    }
+
+    /// turnの側が置けるかどうか
+    pub fn has_legal_move(&self, turn: Turn) -> bool {
+        !self.legal_set(turn).is_empty()
+    }
+
+    /// turnの側が置けるマス目の一覧
+    pub fn legal_moves(&self, turn: Turn) -> Vec<Position> {
+        self.legal_set(turn)
+            .indices()
+            .into_iter()
+            .map(|k| self.position(k))
+            .collect()
+    }
+
+    /// turnの側が(x, y)に置いたときに裏返せる駒のビットの番号
+    ///
+    /// 1マスだけ調べるときは盤面全体をずらすより1マスずつ進む方が速い。
+    fn flip_indices(&self, turn: Turn, x: usize, y: usize) -> Vec<usize> {
+        let (me, opp) = self.sides(turn);
+        let n = self.size as i32;
+        let mut flipped: Vec<usize> = Vec::new();
+        let k = self.index(x, y);
+        if me.contains(k) || opp.contains(k) {
+            return flipped;
+        }
+        for d in &self.directions {
+            let mut line: Vec<usize> = Vec::new();
+            let (mut i, mut j) = (x as i32 + d.dx, y as i32 + d.dy);
+            while 0 <= i && i < n && 0 <= j && j < n {
+                let k = self.index(i as usize, j as usize);
+                if opp.contains(k) {
+                    line.push(k);
+                } else {
+                    // 自分の駒で挟めたときだけ裏返せる
+                    if me.contains(k) {
+                        flipped.append(&mut line);
+                    }
+                    break;
+                }
+                i += d.dx;
+                j += d.dy;
+            }
+        }
+        flipped
+    }
+
+    /// turnの側が(x, y)に置いたときに裏返せる駒の個数
+    pub fn count_flips(&self, turn: Turn, x: usize, y: usize) -> usize {
+        self.flip_indices(turn, x, y).len()
+    }
+
+    /// turnの側が(x, y)に置いて駒を裏返す
+    ///
+    /// 返り値は裏返した駒のマス目。置けるマス目かどうかは確認しない。
+    pub fn put(&mut self, turn: Turn, x: usize, y: usize) -> Vec<Position> {
+        let f = self.flip_indices(turn, x, y);
+        let k = self.index(x, y);
+        let (me, opp) = match turn {
+            Turn::Black => (&mut self.black, &mut self.white),
+            Turn::White => (&mut self.white, &mut self.black),
+        };
+        me.insert(k);
+        for &k in &f {
+            me.insert(k);
+            opp.remove(k);
+        }
+        f.into_iter().map(|k| self.position(k)).collect()
+    }
+}
@@ -0,0 +1,192 @@
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Read};
+
+use crate::boardstate::BoardState;
+use crate::moves::Position;
+
+/// ヘッダーのバイト数
+const HEADER_SIZE: usize = 16;
+
+/// 8x8の対局1つ分のバイト数（対局の情報8バイトと着手60バイト）
+const RECORD_SIZE: usize = 68;
+
+/// 1局に記録される着手の数
+const RECORD_MOVES: usize = 60;
+
+/// WTHORのファイルのヘッダー
+///
+/// createdはファイルを作った日付（年、月、日）、gamesは対局の数、
+/// yearは対局が行われた年、depthは理論値を求めたときの読みの深さ。
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct WthorHeader {
+    pub created: (u16, u8, u8),
+    pub games: u32,
+    pub year: u16,
+    pub depth: u8,
+}
+
+/// WTHORの対局1つ分
+///
+/// tournament/black_player/white_playerは大会と対局者の番号（名前は別のファイルにある）、
+/// black_scoreは終局時の黒の石数、theoretical_scoreは最善を尽くしたときの黒の石数、
+/// movesは着手の列（パスは記録されない）、boardは着手を並べ終えた盤面。
+#[derive(Clone, Debug)]
+pub struct WthorGame {
+    pub tournament: u16,
+    pub black_player: u16,
+    pub white_player: u16,
+    pub black_score: u8,
+    pub theoretical_score: u8,
+    pub moves: Vec<Position>,
+    pub board: BoardState,
+}
+
+/// WTHORのファイルを読めなかった理由
+#[derive(Debug)]
+pub enum WthorError {
+    /// ファイルの読み込みに失敗した（途中で終わっている場合も含む）
+    Io(io::Error),
+    /// 8x8の通常の対局のファイルではない
+    Unsupported,
+    /// 盤面の外を指す着手があった（何局目かは1始まり、何手目かは1始まり）
+    BadSquare(usize, usize, u8),
+    /// 盤面に打てない手があった（何局目かは1始まり、何手目かは1始まり）
+    IllegalMove(usize, usize, Position),
+}
+
+impl fmt::Display for WthorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WthorError::Io(e) => write!(f, "ファイルを読めませんでした（{}）", e),
+            WthorError::Unsupported => write!(f, "8x8の対局のWTHORファイルではありません"),
+            WthorError::BadSquare(game, k, b) => {
+                write!(
+                    f,
+                    "{}局目の{}手目（{}）はマス目を表していません",
+                    game, k, b
+                )
+            }
+            WthorError::IllegalMove(game, k, pos) => {
+                write!(f, "{}局目の{}手目の{}には打てません", game, k, pos)
+            }
+        }
+    }
+}
+
+impl Error for WthorError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            WthorError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for WthorError {
+    fn from(e: io::Error) -> WthorError {
+        WthorError::Io(e)
+    }
+}
+
+/// リトルエンディアンの2バイトを読む
+fn u16_at(bytes: &[u8], k: usize) -> u16 {
+    u16::from_le_bytes([bytes[k], bytes[k + 1]])
+}
+
+/// リトルエンディアンの4バイトを読む
+fn u32_at(bytes: &[u8], k: usize) -> u32 {
+    u32::from_le_bytes([bytes[k], bytes[k + 1], bytes[k + 2], bytes[k + 3]])
+}
+
+/// WTHORのファイルから対局を1つずつ読み出す
+///
+/// 対局ごとに着手を初期局面から並べ直し、```cnt_reversable```で打てる手かを確かめる。
+/// 打てない手がある対局は```Err```になるが、続けて次の対局を読める。
+pub struct WthorReader<R: Read> {
+    reader: R,
+    header: WthorHeader,
+    read: usize,
+}
+
+impl<R: Read> WthorReader<R> {
+    /// ヘッダーを読んで、対局を読み出す準備をする
+    pub fn new(mut reader: R) -> Result<WthorReader<R>, WthorError> {
+        let mut bytes = [0u8; HEADER_SIZE];
+        reader.read_exact(&mut bytes)?;
+        // 12バイト目が盤面のサイズ（0か8が8x8）、13バイト目が対局の種類（0が通常の対局）
+        if !(bytes[12] == 0 || bytes[12] == 8) || bytes[13] != 0 {
+            return Err(WthorError::Unsupported);
+        }
+        let header = WthorHeader {
+            created: (bytes[0] as u16 * 100 + bytes[1] as u16, bytes[2], bytes[3]),
+            games: u32_at(&bytes, 4),
+            year: u16_at(&bytes, 10),
+            depth: bytes[14],
+        };
+        Ok(WthorReader {
+            reader,
+            header,
+            read: 0,
+        })
+    }
+
+    /// ファイルのヘッダー
+    pub fn header(&self) -> WthorHeader {
+        self.header
+    }
+
+    /// 対局の記録1つ分を読んで並べ直す（numberは何局目か）
+    fn read_game(&mut self, number: usize) -> Result<WthorGame, WthorError> {
+        let mut bytes = [0u8; RECORD_SIZE];
+        self.reader.read_exact(&mut bytes)?;
+
+        let mut bs = BoardState::new(4, false);
+        let mut moves: Vec<Position> = Vec::with_capacity(RECORD_MOVES);
+        for (k, &b) in bytes[8..].iter().enumerate() {
+            // 0は着手がないこと（60手より前に終わった対局）を表す
+            if b == 0 {
+                break;
+            }
+            // 行と列がそれぞれ1〜8の2桁の数で書かれている
+            let (row, col) = ((b / 10) as usize, (b % 10) as usize);
+            if !(1..=8).contains(&row) || !(1..=8).contains(&col) {
+                return Err(WthorError::BadSquare(number, k + 1, b));
+            }
+            let pos = Position::new(row - 1, col - 1);
+            if bs.cnt_reversable()[pos.x][pos.y] == 0 {
+                return Err(WthorError::IllegalMove(number, k + 1, pos));
+            }
+            bs.put(pos.x, pos.y);
+            moves.push(pos);
+        }
+
+        Ok(WthorGame {
+            tournament: u16_at(&bytes, 0),
+            black_player: u16_at(&bytes, 2),
+            white_player: u16_at(&bytes, 4),
+            black_score: bytes[6],
+            theoretical_score: bytes[7],
+            moves,
+            board: bs,
+        })
+    }
+}
+
+impl<R: Read> Iterator for WthorReader<R> {
+    type Item = Result<WthorGame, WthorError>;
+
+    /// ヘッダーに書かれた数だけ対局を読む
+    fn next(&mut self) -> Option<Result<WthorGame, WthorError>> {
+        if self.read >= self.header.games as usize {
+            return None;
+        }
+        self.read += 1;
+        let game = self.read_game(self.read);
+        // 読み込みに失敗したらそれ以上は読めないので打ち切る
+        if let Err(WthorError::Io(_)) = game {
+            self.read = self.header.games as usize;
+        }
+        Some(game)
+    }
+}
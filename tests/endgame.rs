@@ -0,0 +1,92 @@
+use rand::rngs::SmallRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+use simple_reversi_2::endgame::{solve, GameResult};
+use simple_reversi_2::search::{search, Level};
+use simple_reversi_2::{BoardState, Turn};
+
+/// playerから見た石差
+fn disc_diff(bs: &BoardState, player: Turn) -> i32 {
+    let ((_, white), (_, black)) = bs.count_pieces();
+    let diff = white as i32 - black as i32;
+    match player {
+        Turn::White => diff,
+        Turn::Black => -diff,
+    }
+}
+
+/// 枝刈りをしない素朴なミニマックス法で、playerから見た終局時の石差を求める
+fn minimax(bs: &BoardState, player: Turn) -> i32 {
+    if bs.is_game_over() {
+        return disc_diff(bs, player);
+    }
+    let scores = bs.legal_moves().into_iter().map(|pos| {
+        let mut next = bs.clone();
+        next.put(pos.x, pos.y);
+        minimax(&next, player)
+    });
+    if bs.turn() == player {
+        scores.max().unwrap()
+    } else {
+        scores.min().unwrap()
+    }
+}
+
+/// 空きマスがempties個になるまでランダムに打ち進めた局面（途中で終局したらNone）
+fn random_position(n: usize, empties: usize, rng: &mut SmallRng) -> Option<BoardState> {
+    let mut bs = BoardState::new(n, false);
+    while bs.count_empties() > empties {
+        let pos = *bs.legal_moves().choose(rng)?;
+        bs.put(pos.x, pos.y);
+    }
+    if bs.is_game_over() {
+        None
+    } else {
+        Some(bs)
+    }
+}
+
+#[test]
+fn solve_4x4_from_the_start() {
+    // 4x4は両者最善なら白が11対3で勝つ
+    let result = solve(&BoardState::new(2, false));
+    assert_eq!(result.disc_diff, -8);
+    assert_eq!(result.result, GameResult::Loss);
+    assert!(result.best_move.is_some());
+}
+
+#[test]
+fn solve_matches_minimax() {
+    let mut rng = SmallRng::seed_from_u64(2021);
+    let mut checked = 0;
+    while checked < 30 {
+        let bs = match random_position(4, 7, &mut rng) {
+            Some(bs) => bs,
+            None => continue,
+        };
+        let expected = minimax(&bs, bs.turn());
+        let result = solve(&bs);
+        assert_eq!(result.disc_diff, expected, "{:?}", bs.show_board());
+
+        // 最善手を打った後の局面も同じ石差になる
+        let pos = result.best_move.unwrap();
+        let mut next = bs.clone();
+        next.put(pos.x, pos.y);
+        assert_eq!(minimax(&next, bs.turn()), expected);
+        checked += 1;
+    }
+}
+
+#[test]
+fn search_switches_to_the_solver_near_the_end() {
+    let mut rng = SmallRng::seed_from_u64(7);
+    let bs = loop {
+        if let Some(bs) = random_position(4, 8, &mut rng) {
+            break bs;
+        }
+    };
+    let result = search(&bs, Level::Strong.limits());
+    assert_eq!(result.depth, bs.count_empties());
+    assert_eq!(result.best_move, solve(&bs).best_move);
+}
@@ -0,0 +1,132 @@
+use simple_reversi_2::ggf::{parse_games, GgfError, GgfGame};
+use simple_reversi_2::{BoardState, Move, MoveError, Position, Turn};
+
+/// 4x4でランダムなCPU同士が打った1局（途中で白のパスがある）
+const FINISHED_4X4: &str = "(;GM[Othello]PC[simple-reversi-2]PB[cpu-random]PW[cpu-random]\
+RE[-2.000]TY[4]BO[4 ---- -O*- -*O- ---- *]B[c4]W[d4]B[d3]W[b4]B[a1]W[d1]B[b1]W[d2]B[a4]\
+W[a2]B[a3]W[PA]B[c1];)";
+
+#[test]
+fn reads_a_finished_game() {
+    let game: GgfGame = FINISHED_4X4.parse().unwrap();
+    assert_eq!(game.size, 4);
+    assert_eq!(game.black, "cpu-random");
+    assert_eq!(game.result, Some(-2));
+    assert_eq!(game.moves.len(), 13);
+    assert_eq!(game.moves[11], Move::Pass);
+
+    let bs = game.to_board().unwrap();
+    assert!(bs.is_game_over());
+    let ((_, white), (_, black)) = bs.count_pieces();
+    assert_eq!(black as i32 - white as i32, -2);
+    assert_eq!(bs.transcript(), "c4d4d3b4a1d1b1d2a4a2a3c1");
+}
+
+#[test]
+fn written_games_read_back_the_same() {
+    let game: GgfGame = FINISHED_4X4.parse().unwrap();
+    let bs = game.to_board().unwrap();
+    let written = GgfGame::from_board(&bs, "cpu-random", "cpu-random").to_string();
+    assert_eq!(written, FINISHED_4X4);
+
+    // 途中の局面から始まる対局や、名前に]を含む対局も書いて読み直せる
+    let mut bs: BoardState = "..../.o#./.##./...# o".parse().unwrap();
+    let pos = bs.legal_moves()[0];
+    bs.put(pos.x, pos.y);
+    let game = GgfGame::from_board(&bs, "a]b", "c\\d");
+    let read: GgfGame = game.to_string().parse().unwrap();
+    assert_eq!(read.black, "a]b");
+    assert_eq!(read.white, "c\\d");
+    assert_eq!(read.result, None);
+    assert_eq!(read.start.to_string(), "..../.o#./.##./...# o");
+    assert_eq!(read.to_board().unwrap().to_string(), bs.to_string());
+}
+
+#[test]
+fn empty_squares_count_for_the_winner() {
+    // a1に置くと白が3個、黒が0個で終わり、空きマスの13個は白のものになる
+    let mut bs: BoardState = ".#o./..../..../.... o".parse().unwrap();
+    bs.put(0, 0);
+    let game = GgfGame::from_board(&bs, "a", "b");
+    assert_eq!(game.result, Some(-16));
+    assert!(game.to_string().contains("RE[-16.000]"));
+}
+
+#[test]
+fn reads_server_style_records() {
+    // 対局サーバーの棋譜のように、評価値や時間の付いた手や知らない項目があっても読める
+    let text = "\n(;GM[Othello]PC[GGS/os]DT[2003.12.15_13:24:03.MST]PB[alice]PW[bob]\
+RB[2197.61]RW[2229.05]TI[5:00//02:00]TY[8]RE[+4.000:r]\
+BO[8 -------- -------- -------- ---O*--- ---*O--- -------- -------- -------- *]\
+B[F5//0.01]W[d6/1.5/0.02]B[C3];)\n  (;GM[Othello]TY[8]\
+BO[8 -------- -------- -------- ---O*--- ---*O--- -------- -------- -------- *]B[f5];)\n";
+    let games = parse_games(text).unwrap();
+    assert_eq!(games.len(), 2);
+    assert_eq!(games[0].result, Some(4));
+    assert_eq!(games[0].to_board().unwrap().transcript(), "f5d6c3");
+    assert_eq!(games[1].to_board().unwrap().transcript(), "f5");
+}
+
+#[test]
+fn reads_unfinished_results_and_comments_with_terminators() {
+    // 終わっていない対局はRE[?]になり、コメントの中の;)や\]は対局の終わりではない
+    let text = "(;GM[Othello]TY[8]RE[?]GC[good game ;)]\
+BO[8 -------- -------- -------- ---O*--- ---*O--- -------- -------- -------- *]B[f5]\
+C[a\\];) b]W[d6];)\n(;GM[Othello]TY[8]RE[+64.000]\
+BO[8 -------- -------- -------- ---O*--- ---*O--- -------- -------- -------- *];)";
+    let games = parse_games(text).unwrap();
+    assert_eq!(games.len(), 2);
+    assert_eq!(games[0].result, None);
+    assert_eq!(games[0].to_board().unwrap().transcript(), "f5d6");
+    assert_eq!(games[1].result, Some(64));
+}
+
+#[test]
+fn reads_games_that_start_with_a_pass() {
+    // 黒は置けないのでパスから始まる
+    let game: GgfGame = "(;GM[Othello]TY[4]BO[4 O*-- ---- ---- ---- *]B[PA]W[c1];)"
+        .parse()
+        .unwrap();
+    assert_eq!(game.start.turn(), Turn::White);
+    assert_eq!(game.moves, vec![Move::Put(Position::new(0, 2))]);
+    let bs = game.to_board().unwrap();
+    assert!(bs.is_game_over());
+    assert_eq!(bs.to_string(), "ooo./..../..../.... o");
+
+    // パスを書かずに白の手から始めてもよい
+    let game: GgfGame = "(;GM[Othello]TY[4]BO[4 O*-- ---- ---- ---- *]W[c1];)"
+        .parse()
+        .unwrap();
+    assert!(game.to_board().unwrap().is_game_over());
+}
+
+#[test]
+fn rejects_broken_records() {
+    let board = "BO[8 -------- -------- -------- ---O*--- ---*O--- -------- -------- -------- *]";
+    let parse = |body: &str| format!("(;GM[Othello]{};)", body).parse::<GgfGame>();
+
+    assert_eq!(parse("B[f5]").unwrap_err(), GgfError::Missing("BO"));
+    assert_eq!(
+        parse(&format!("{}B[f5]B[d6]", board)).unwrap_err(),
+        GgfError::WrongTurn(2)
+    );
+    assert_eq!(
+        parse(&format!("{}B[f5]W[PA]", board))
+            .unwrap()
+            .to_board()
+            .unwrap_err(),
+        GgfError::WrongTurn(2)
+    );
+    assert_eq!(
+        parse(&format!("{}B[a1]", board))
+            .unwrap()
+            .to_board()
+            .unwrap_err(),
+        GgfError::IllegalMove(1, Position::new(0, 0), MoveError::NoFlip)
+    );
+    assert!(matches!(parse("BO[8 ---- *]"), Err(GgfError::Syntax(_))));
+    assert!(matches!(
+        "(;GM[Chess]TY[8];)".parse::<GgfGame>(),
+        Err(GgfError::Syntax(_))
+    ));
+}
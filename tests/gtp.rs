@@ -0,0 +1,151 @@
+use simple_reversi_2::gtp::{run, GtpEngine, GtpError};
+use simple_reversi_2::{MoveError, Turn};
+
+/// 命令を順に渡して、応答（空行で区切られたもの）を返す
+fn respond(commands: &[&str]) -> Vec<String> {
+    let mut out: Vec<u8> = Vec::new();
+    run(commands.join("\n").as_bytes(), &mut out).unwrap();
+    String::from_utf8(out)
+        .unwrap()
+        .split("\n\n")
+        .filter(|r| !r.is_empty())
+        .map(|r| r.to_string())
+        .collect()
+}
+
+#[test]
+fn answers_with_ids_and_errors() {
+    let responses = respond(&[
+        "1 protocol_version",
+        "name # 注釈",
+        "",
+        "2 known_command genmove",
+        "known_command foo",
+        "3 foo",
+        "boardsize 5",
+        "play b",
+        "quit",
+        "name",
+    ]);
+    assert_eq!(
+        responses,
+        vec![
+            "=1 2",
+            "= simple-reversi-2",
+            "=2 true",
+            "= false",
+            "?3 unknown command",
+            "? unacceptable size",
+            "? syntax error",
+            "=",
+        ]
+    );
+}
+
+#[test]
+fn plays_and_undoes_moves() {
+    let mut engine = GtpEngine::new();
+    assert_eq!(engine.execute("play", &["b", "F5"]), Ok(String::new()));
+    assert_eq!(
+        engine.execute("play", &["b", "d6"]),
+        Err(GtpError::WrongColor)
+    );
+    assert_eq!(
+        engine.execute("play", &["w", "a1"]),
+        Err(GtpError::IllegalMove(MoveError::NoFlip))
+    );
+    assert_eq!(
+        engine.execute("play", &["w", "i9"]),
+        Err(GtpError::IllegalMove(MoveError::OutOfBounds))
+    );
+    assert_eq!(
+        engine.execute("play", &["w", "pass"]),
+        Err(GtpError::IllegalPass)
+    );
+    assert_eq!(engine.execute("play", &["w", "d6"]), Ok(String::new()));
+    assert_eq!(engine.board().transcript(), "f5d6");
+
+    assert_eq!(engine.execute("undo", &[]), Ok(String::new()));
+    assert_eq!(engine.execute("undo", &[]), Ok(String::new()));
+    assert_eq!(engine.execute("undo", &[]), Err(GtpError::CannotUndo));
+    assert_eq!(engine.execute("final_score", &[]), Ok("0".to_string()));
+}
+
+#[test]
+fn accepts_passes_made_automatically() {
+    // 4x4でこの順に打つと6手目の後に黒がパスになる
+    let mut engine = GtpEngine::new();
+    engine.execute("boardsize", &["4"]).unwrap();
+    let moves = ["b1", "a1", "d3", "c1", "a2", "a3"];
+    for (k, mv) in moves.iter().enumerate() {
+        let color = if k % 2 == 0 { "b" } else { "w" };
+        assert_eq!(engine.execute("play", &[color, mv]), Ok(String::new()));
+    }
+    assert_eq!(engine.execute("play", &["b", "pass"]), Ok(String::new()));
+    assert_eq!(engine.execute("genmove", &["b"]), Ok("pass".to_string()));
+    assert!(engine.execute("genmove", &["w"]).is_ok());
+}
+
+#[test]
+fn genmove_plays_a_whole_game() {
+    let mut engine = GtpEngine::new();
+    engine.execute("boardsize", &["6"]).unwrap();
+    let mut color = "b";
+    while !engine.board().is_game_over() {
+        let vertex = engine.execute("genmove", &[color]).unwrap();
+        assert!(!vertex.is_empty());
+        color = if color == "b" { "w" } else { "b" };
+    }
+    assert_eq!(engine.execute("genmove", &["w"]), Ok("pass".to_string()));
+    let score = engine.execute("final_score", &[]).unwrap();
+    assert!(score == "0" || score.starts_with("B+") || score.starts_with("W+"));
+
+    engine.execute("clear_board", &[]).unwrap();
+    assert_eq!(engine.board().get_size(), 6);
+    assert_eq!(engine.board().history().len(), 0);
+}
+
+#[test]
+fn shows_the_board() {
+    let board = GtpEngine::new().execute("showboard", &[]).unwrap();
+    let lines: Vec<&str> = board.lines().collect();
+    assert_eq!(lines[0], "");
+    assert_eq!(lines[1], "  a b c d e f g h");
+    assert_eq!(lines.len(), 11);
+}
+
+#[test]
+fn undo_after_an_acknowledged_pass_keeps_the_move_before_it() {
+    // 4x4でこの順に打つと6手目の後に黒がパスになる
+    let mut engine = GtpEngine::new();
+    engine.execute("boardsize", &["4"]).unwrap();
+    let moves = ["b1", "a1", "d3", "c1", "a2", "a3"];
+    for (k, mv) in moves.iter().enumerate() {
+        let color = if k % 2 == 0 { "b" } else { "w" };
+        engine.execute("play", &[color, mv]).unwrap();
+    }
+    engine.execute("play", &["b", "pass"]).unwrap();
+    let after_pass = engine.board().to_string();
+
+    // 取り消すのは黒のパスだけで、白のa3は残る
+    assert_eq!(engine.execute("undo", &[]), Ok(String::new()));
+    assert_eq!(engine.board().transcript(), "b1a1d3c1a2a3");
+    assert_eq!(engine.board().turn(), Turn::Black);
+    assert_eq!(
+        engine.execute("play", &["w", "d4"]),
+        Err(GtpError::WrongColor)
+    );
+
+    // パスを打ち直せば元の局面に戻る
+    assert_eq!(engine.execute("play", &["b", "pass"]), Ok(String::new()));
+    assert_eq!(engine.board().to_string(), after_pass);
+    assert_eq!(engine.execute("undo", &[]), Ok(String::new()));
+    assert_eq!(engine.execute("genmove", &["b"]), Ok("pass".to_string()));
+    assert_eq!(engine.board().to_string(), after_pass);
+
+    // パスの前の着手はもう一度undoすると取り消せる
+    engine.execute("undo", &[]).unwrap();
+    assert_eq!(engine.execute("undo", &[]), Ok(String::new()));
+    assert_eq!(engine.board().transcript(), "b1a1d3c1a2");
+    assert_eq!(engine.board().turn(), Turn::White);
+}
@@ -0,0 +1,64 @@
+use simple_reversi_2::{BoardState, HistoryEntry, Move, Position, Turn};
+
+/// 白がc1に置くと黒がパスになり、白はその後d4に置ける局面
+const FORCES_PASS: &str = "o#.#/...o/...#/.... o";
+
+#[test]
+fn undo_removes_the_recorded_pass_with_the_move_before_it() {
+    let mut bs: BoardState = FORCES_PASS.parse().unwrap();
+    bs.put(0, 2);
+    assert_eq!(
+        bs.history().last(),
+        Some(&HistoryEntry {
+            player: Turn::Black,
+            mv: Move::Pass,
+            flipped: Vec::new(),
+        })
+    );
+
+    assert_eq!(bs.undo(), Some(Position::new(0, 2)));
+    assert!(bs.history().is_empty());
+    assert_eq!(bs.to_string(), FORCES_PASS);
+    assert_eq!(bs.undo(), None);
+}
+
+#[test]
+fn undo_after_a_pass_only_takes_back_the_last_move() {
+    let mut bs: BoardState = FORCES_PASS.parse().unwrap();
+    bs.put(0, 2);
+    let after_pass = bs.to_string();
+    bs.put(3, 3);
+
+    assert_eq!(bs.undo(), Some(Position::new(3, 3)));
+    assert_eq!(bs.to_string(), after_pass);
+    assert_eq!(bs.history().len(), 2);
+    assert_eq!(bs.turn(), Turn::White);
+}
+
+#[test]
+fn redo_replays_undone_moves_in_order() {
+    let mut bs: BoardState = FORCES_PASS.parse().unwrap();
+    let first = bs.put(0, 2);
+    let second = bs.put(3, 3);
+    let end = bs.to_string();
+    let history = bs.history().to_vec();
+
+    bs.undo();
+    bs.undo();
+    assert_eq!(bs.redo(), Some(first));
+    assert_eq!(bs.redo(), Some(second));
+    assert_eq!(bs.redo(), None);
+    assert_eq!(bs.to_string(), end);
+    // パスも記録し直される
+    assert_eq!(bs.history(), &history[..]);
+}
+
+#[test]
+fn a_new_move_clears_the_moves_to_redo() {
+    let mut bs = BoardState::new(2, false);
+    bs.put(0, 1);
+    bs.undo();
+    bs.try_put(1, 0).unwrap();
+    assert_eq!(bs.redo(), None);
+    assert_eq!(bs.transcript(), "a2");
+}
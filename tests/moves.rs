@@ -0,0 +1,104 @@
+use simple_reversi_2::{BoardState, MoveError, MoveOutcome, Position, Turn};
+
+/// try_putが失敗したときに盤面も履歴も変わっていないことを確かめる
+fn assert_rejected(bs: &mut BoardState, i: usize, j: usize, expected: MoveError) {
+    let board = bs.to_string();
+    let history = bs.history().to_vec();
+    assert_eq!(bs.try_put(i, j), Err(expected));
+    assert_eq!(bs.to_string(), board);
+    assert_eq!(bs.history(), &history[..]);
+}
+
+#[test]
+fn try_put_rejects_squares_outside_the_board() {
+    let mut bs = BoardState::new(2, false);
+    assert_rejected(&mut bs, 4, 0, MoveError::OutOfBounds);
+    assert_rejected(&mut bs, 0, 4, MoveError::OutOfBounds);
+}
+
+#[test]
+fn try_put_rejects_occupied_squares() {
+    let mut bs = BoardState::new(2, false);
+    assert_rejected(&mut bs, 1, 1, MoveError::Occupied);
+    assert_rejected(&mut bs, 2, 1, MoveError::Occupied);
+}
+
+#[test]
+fn try_put_rejects_squares_that_flip_nothing() {
+    let mut bs = BoardState::new(2, false);
+    assert_rejected(&mut bs, 0, 0, MoveError::NoFlip);
+    // 白なら置けるマス目でも黒の番では置けない
+    assert_rejected(&mut bs, 0, 2, MoveError::NoFlip);
+}
+
+#[test]
+fn try_put_reports_game_over_before_anything_else() {
+    // どちらも置けない局面では、範囲外や置いてあるマス目でもGameOverになる
+    let mut bs: BoardState = "o.../..../..../.... #".parse().unwrap();
+    assert!(bs.is_game_over());
+    assert_rejected(&mut bs, 9, 9, MoveError::GameOver);
+    assert_rejected(&mut bs, 0, 0, MoveError::GameOver);
+    assert_rejected(&mut bs, 0, 1, MoveError::GameOver);
+}
+
+#[test]
+fn a_rejected_move_keeps_the_moves_to_redo() {
+    let mut bs = BoardState::new(2, false);
+    let outcome = bs.put(0, 1);
+    bs.undo();
+    assert_rejected(&mut bs, 0, 0, MoveError::NoFlip);
+    assert_eq!(bs.redo(), Some(outcome));
+}
+
+/// 裏返した駒を並べ替えた着手の結果（裏返す順番は方向の調べ方によるので比べない）
+fn sorted(mut outcome: MoveOutcome) -> MoveOutcome {
+    outcome.flipped.sort_by_key(|p| (p.x, p.y));
+    outcome
+}
+
+#[test]
+fn outcome_lists_flips_in_every_direction() {
+    // b2に置くと右、下、右下の3方向で1つずつ裏返る
+    let mut bs: BoardState = "..#o/..o#/.oo./.#.# #".parse().unwrap();
+    let outcome = sorted(bs.put(1, 1));
+    assert_eq!(
+        outcome,
+        MoveOutcome {
+            player: Turn::Black,
+            position: Position::new(1, 1),
+            flipped: vec![
+                Position::new(1, 2),
+                Position::new(2, 1),
+                Position::new(2, 2)
+            ],
+            opponent_passed: false,
+            game_over: false,
+        }
+    );
+    assert_eq!(bs.to_string(), "..#o/.###/.##./.#.# o");
+    assert_eq!(bs.turn(), Turn::White);
+}
+
+#[test]
+fn outcome_reports_a_forced_pass() {
+    // c1に置くと黒は置けなくなるが、白はまだd4に置ける
+    let mut bs: BoardState = "o#.#/...o/...#/.... o".parse().unwrap();
+    let outcome = bs.put(0, 2);
+    assert_eq!(outcome.flipped, vec![Position::new(0, 1)]);
+    assert!(outcome.opponent_passed);
+    assert!(!outcome.game_over);
+    assert_eq!(bs.turn(), Turn::White);
+    assert_eq!(bs.legal_moves(), vec![Position::new(3, 3)]);
+}
+
+#[test]
+fn outcome_reports_the_last_move_of_the_game() {
+    // a1に置くと黒の駒がなくなり、どちらも置けなくなる
+    let mut bs: BoardState = ".#o./..../..../.... o".parse().unwrap();
+    let outcome = bs.put(0, 0);
+    assert_eq!(outcome.flipped, vec![Position::new(0, 1)]);
+    assert!(outcome.game_over);
+    // ゲームが終わったときはパスとは報告しない
+    assert!(!outcome.opponent_passed);
+    assert!(bs.is_game_over());
+}
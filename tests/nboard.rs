@@ -0,0 +1,145 @@
+use simple_reversi_2::ggf::GgfGame;
+use simple_reversi_2::nboard::{run, NboardEngine};
+use simple_reversi_2::{BoardState, Position};
+
+/// 命令を順に渡して、応答を行ごとに返す
+fn respond(commands: &[&str]) -> Vec<String> {
+    let mut out: Vec<u8> = Vec::new();
+    run(commands.join("\n").as_bytes(), &mut out).unwrap();
+    String::from_utf8(out)
+        .unwrap()
+        .lines()
+        .map(|l| l.to_string())
+        .collect()
+}
+
+/// 8x8の初期局面から2手進んだGGF
+const GAME: &str = "(;GM[Othello]PC[NBoard]PB[a]PW[b]TY[8]\
+BO[8 -------- -------- -------- ---O*--- ---*O--- -------- -------- -------- *]\
+B[F5//1.23]W[D6];)";
+
+/// NBoardのGUIが実際に送ってくる形のGGF（結果は```RE[?]```、盤面は64マスを1つにつなげた形）
+const NBOARD_GAME: &str = "(;GM[Othello]PC[NBoard]DT[2021-05-03 12:34:56 GMT]PB[Human]\
+PW[simple-reversi-2]RE[?]TI[5:00]TY[8]\
+BO[8 ---------------------------O*------*O--------------------------- *]\
+B[F5]W[D6//0.01]B[C3];)";
+
+#[test]
+fn answers_handshake_and_ping() {
+    let lines = respond(&["nboard 2", "set depth 2", "ping 3", "quit", "ping 4"]);
+    assert_eq!(lines, vec!["set myname simple-reversi-2", "pong 3"]);
+}
+
+#[test]
+fn tracks_the_game_from_set_game_and_move() {
+    let mut engine = NboardEngine::new();
+    let mut out: Vec<u8> = Vec::new();
+    engine
+        .handle(&format!("set game {}", GAME), &mut out)
+        .unwrap();
+    engine.handle("move C3/0.50/1.2", &mut out).unwrap();
+    assert!(out.is_empty(), "{}", String::from_utf8_lossy(&out));
+    assert_eq!(engine.board().transcript(), "f5d6c3");
+
+    // 打てない手やパスできない局面のパスは無視して知らせる
+    engine.handle("move A1", &mut out).unwrap();
+    engine.handle("move PA", &mut out).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert_eq!(out.lines().count(), 2);
+    assert!(out.lines().all(|l| l.starts_with("status ")));
+    assert_eq!(engine.board().transcript(), "f5d6c3");
+}
+
+#[test]
+fn follows_a_game_sent_by_the_nboard_gui() {
+    let mut engine = NboardEngine::new();
+    let mut out: Vec<u8> = Vec::new();
+    engine
+        .handle(&format!("set game {}", NBOARD_GAME), &mut out)
+        .unwrap();
+    engine.handle("move D3", &mut out).unwrap();
+    assert!(out.is_empty(), "{}", String::from_utf8_lossy(&out));
+    assert_eq!(engine.board().transcript(), "f5d6c3d3");
+}
+
+#[test]
+fn accepts_the_pass_after_a_move_that_forces_one() {
+    // d8を打つと相手はパスになり、続けてa1に打てる
+    let before = BoardState::from_transcript(
+        4,
+        "d3e3f6c2e2f1e1f3c4e6f5c5c6b5g1d6a4b7c7g4e7f7g5a5a7e8b4h5g2b8d1g6f2h1c8a8d7a6h2a3c3c1g3\
+d2g8g7f8f4h3h8h6h4b1h7b2",
+    )
+    .unwrap();
+    let game = GgfGame::from_board(&before, "a", "b");
+    let mut engine = NboardEngine::new();
+    let mut out: Vec<u8> = Vec::new();
+    engine
+        .handle(&format!("set game {}", game), &mut out)
+        .unwrap();
+    engine.handle("move D8", &mut out).unwrap();
+    assert_eq!(engine.board().turn(), before.turn());
+    engine.handle("move PA", &mut out).unwrap();
+    engine.handle("move A1", &mut out).unwrap();
+    assert!(out.is_empty(), "{}", String::from_utf8_lossy(&out));
+    assert!(engine.board().transcript().ends_with("b2d8a1"));
+}
+
+#[test]
+fn go_answers_a_legal_move_without_playing_it() {
+    let mut engine = NboardEngine::new();
+    let mut out: Vec<u8> = Vec::new();
+    engine.handle("set depth 3", &mut out).unwrap();
+    engine.handle("go", &mut out).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    let answer = out.lines().find(|l| l.starts_with("=== ")).unwrap();
+    let square = answer[4..].split('/').next().unwrap();
+    let pos: Position = square.parse().unwrap();
+    assert!(BoardState::new(4, false).legal_moves().contains(&pos));
+    assert_eq!(engine.board().history().len(), 0);
+    assert_eq!(engine.limits().depth, 3);
+}
+
+#[test]
+fn go_passes_when_the_game_is_over() {
+    let mut engine = NboardEngine::new();
+    let mut out: Vec<u8> = Vec::new();
+    let over = "(;GM[Othello]TY[8]BO[8 ******** ******** ******** ******** \
+******** ******** ******** ******** O];)";
+    engine
+        .handle(&format!("set game {}", over), &mut out)
+        .unwrap();
+    engine.handle("go", &mut out).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.lines().any(|l| l == "=== PA"), "{}", out);
+}
+
+#[test]
+fn hint_lists_the_best_moves() {
+    let lines = respond(&["set depth 2", "hint 3", "ping 1"]);
+    let hints: Vec<&String> = lines.iter().filter(|l| l.starts_with("search ")).collect();
+    assert_eq!(hints.len(), 3);
+    let evals: Vec<f64> = hints
+        .iter()
+        .map(|l| l.split_whitespace().nth(2).unwrap().parse().unwrap())
+        .collect();
+    assert!(evals.windows(2).all(|w| w[0] >= w[1]));
+    assert_eq!(lines.last().unwrap(), "pong 1");
+}
+
+#[test]
+fn hint_answers_a_pass_when_there_is_no_move() {
+    let over = "(;GM[Othello]TY[8]BO[8 ******** ******** ******** ******** \
+******** ******** ******** ******** O];)";
+    let lines = respond(&[&format!("set game {}", over), "set depth 3", "hint 2"]);
+    let hints: Vec<&String> = lines.iter().filter(|l| l.starts_with("search ")).collect();
+    assert_eq!(hints, vec!["search PA -64.00 0 3"]);
+    assert_eq!(lines.last().unwrap(), "status");
+}
+
+#[test]
+fn reports_unknown_commands_and_bad_games() {
+    let lines = respond(&["foo", "set game (;GM[Chess];)", "set depth 0"]);
+    assert_eq!(lines.len(), 3);
+    assert!(lines.iter().all(|l| l.starts_with("status ")));
+}
@@ -0,0 +1,147 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+
+use simple_reversi_2::netplay::{
+    accept_game, join_game, Message, NetError, NetGame, RemotePlayer, MAX_SIZE, PROTOCOL_VERSION,
+};
+use simple_reversi_2::player::{Action, Player};
+use simple_reversi_2::{BoardState, Position, Turn};
+
+/// localhostで待ち受けて接続し、（待ち受けた側、接続した側）の組を返す
+fn connect(size: usize, host_color: Turn) -> (NetGame, NetGame) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let host = thread::spawn(move || accept_game(&listener, size, host_color).unwrap());
+    let guest = join_game(addr).unwrap();
+    (host.join().unwrap(), guest)
+}
+
+#[test]
+fn messages_read_back_the_same() {
+    let messages = [
+        Message::Hello(PROTOCOL_VERSION),
+        Message::Game {
+            size: 10,
+            host: Turn::White,
+        },
+        Message::Game {
+            size: MAX_SIZE,
+            host: Turn::Black,
+        },
+        Message::Move(Position::new(4, 5)),
+        Message::Quit,
+    ];
+    for m in messages.iter() {
+        assert_eq!(m.to_string().parse::<Message>().unwrap(), *m);
+    }
+    assert_eq!(Message::Move(Position::new(4, 5)).to_string(), "move f5");
+    for bad in [
+        "",
+        "move",
+        "move 5f",
+        "game 5 black",
+        "game 8 red",
+        "game 28 black",
+        "game 1000000000 black",
+        "hello x",
+    ]
+    .iter()
+    {
+        assert!(matches!(bad.parse::<Message>(), Err(NetError::Protocol(_))));
+    }
+}
+
+#[test]
+fn both_sides_agree_on_size_and_colors() {
+    let (host, guest) = connect(6, Turn::White);
+    assert_eq!((host.size, host.my_color), (6, Turn::White));
+    assert_eq!((guest.size, guest.my_color), (6, Turn::Black));
+}
+
+#[test]
+fn remote_moves_are_validated_against_the_board() {
+    let (mut host, guest) = connect(8, Turn::Black);
+    let mut remote = RemotePlayer::new(guest.connection);
+    let mut bs = BoardState::new(4, false);
+
+    host.connection
+        .send(Message::Move(Position::new(4, 5)))
+        .unwrap();
+    assert_eq!(remote.play(&bs).unwrap(), Action::Put(Position::new(4, 5)));
+    bs.put(4, 5);
+
+    // 裏返せないa1や盤面の外のマス目は打てない
+    host.connection
+        .send(Message::Move(Position::new(0, 0)))
+        .unwrap();
+    assert!(matches!(
+        remote.receive_move(&bs),
+        Err(NetError::IllegalMove(_))
+    ));
+    host.connection
+        .send(Message::Move(Position::new(9, 9)))
+        .unwrap();
+    assert!(matches!(
+        remote.receive_move(&bs),
+        Err(NetError::IllegalMove(_))
+    ));
+
+    host.connection.send(Message::Quit).unwrap();
+    assert!(matches!(remote.receive_move(&bs), Err(NetError::Quit)));
+
+    drop(host);
+    assert!(matches!(
+        remote.receive_move(&bs),
+        Err(NetError::Disconnected)
+    ));
+    // Playerとしては接続が切れたことをエラーで返す
+    assert!(remote.play(&bs).is_err());
+}
+
+#[test]
+fn rejects_a_different_protocol_version() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let host = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut writer = stream;
+        writeln!(writer, "hello {}", PROTOCOL_VERSION + 1).unwrap();
+        // 相手が諦めて切断するまで待つ
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+    });
+    match join_game(addr) {
+        Err(NetError::Version(v)) => assert_eq!(v, PROTOCOL_VERSION + 1),
+        r => panic!("{:?}", r.map(|g| g.size)),
+    }
+    host.join().unwrap();
+}
+
+#[test]
+fn host_reports_a_guest_that_leaves_during_the_handshake() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let guest = thread::spawn(move || drop(TcpStream::connect(addr).unwrap()));
+    let result = accept_game(&listener, 8, Turn::Black);
+    guest.join().unwrap();
+    assert!(matches!(
+        result,
+        Err(NetError::Disconnected) | Err(NetError::Io(_))
+    ));
+}
+
+#[test]
+fn shutdown_wakes_up_a_waiting_receiver() {
+    let (_host, guest) = connect(8, Turn::Black);
+    let closer = guest.connection.try_clone().unwrap();
+    let mut remote = RemotePlayer::new(guest.connection);
+    let bs = BoardState::new(4, false);
+    let waiting = thread::spawn(move || remote.receive_move(&bs));
+    closer.shutdown().unwrap();
+    assert!(matches!(
+        waiting.join().unwrap(),
+        Err(NetError::Disconnected)
+    ));
+}
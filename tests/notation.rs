@@ -0,0 +1,84 @@
+use simple_reversi_2::notation::{column_index, column_name, parse_transcript, NotationError};
+use simple_reversi_2::{BoardState, MoveError, Position};
+
+#[test]
+fn column_names_continue_past_z() {
+    let names = [
+        (0, "a"),
+        (7, "h"),
+        (25, "z"),
+        (26, "aa"),
+        (27, "ab"),
+        (701, "zz"),
+        (702, "aaa"),
+    ];
+    for &(y, name) in &names {
+        assert_eq!(column_name(y), name);
+        assert_eq!(column_index(name), Some(y));
+    }
+    assert_eq!(column_index("AB"), Some(27));
+    assert_eq!(column_index(""), None);
+}
+
+#[test]
+fn squares_print_and_parse() {
+    assert_eq!(Position::new(4, 5).to_string(), "f5");
+    assert_eq!("f5".parse(), Ok(Position::new(4, 5)));
+    assert_eq!("AA30".parse(), Ok(Position::new(29, 26)));
+    assert_eq!("f0".parse::<Position>(), Err(NotationError::Syntax(1)));
+    assert_eq!("5f".parse::<Position>(), Err(NotationError::Syntax(0)));
+    assert_eq!("f5x".parse::<Position>(), Err(NotationError::Syntax(2)));
+}
+
+#[test]
+fn transcripts_parse_with_or_without_spaces() {
+    let expected = vec![
+        Position::new(4, 5),
+        Position::new(5, 3),
+        Position::new(2, 2),
+    ];
+    assert_eq!(parse_transcript("f5d6c3"), Ok(expected.clone()));
+    assert_eq!(parse_transcript("F5 D6 C3\n"), Ok(expected));
+    assert_eq!(parse_transcript(""), Ok(vec![]));
+    assert_eq!(parse_transcript("f5d6-c3"), Err(NotationError::Syntax(4)));
+}
+
+#[test]
+fn from_transcript_replays_and_round_trips() {
+    let bs = BoardState::from_transcript(4, "f5d6c3d3c4").unwrap();
+    assert_eq!(bs.transcript(), "f5d6c3d3c4");
+    assert_eq!(bs.history().len(), 5);
+    assert!(bs.is_it_white_turn());
+
+    // パスを含む棋譜も並べ直せる（4x4で白が2回パスする）
+    let with_pass = BoardState::from_transcript(2, "b1a1d3c1a2a3").unwrap();
+    assert!(with_pass.history().len() > 6);
+    assert_eq!(with_pass.transcript(), "b1a1d3c1a2a3");
+
+    // 10x10以上では行番号が2桁になる
+    let mut big = BoardState::new(5, false);
+    while !big.is_game_over() {
+        let pos = *big.legal_moves().last().unwrap();
+        big.put(pos.x, pos.y);
+    }
+    assert!(big.transcript().contains("10"));
+    let replayed = BoardState::from_transcript(5, &big.transcript()).unwrap();
+    assert_eq!(replayed.show_board(), big.show_board());
+    assert_eq!(replayed.history(), big.history());
+}
+
+#[test]
+fn from_transcript_reports_the_illegal_move() {
+    assert_eq!(
+        BoardState::from_transcript(4, "f5f5").unwrap_err(),
+        NotationError::IllegalMove(2, Position::new(4, 5), MoveError::Occupied)
+    );
+    assert_eq!(
+        BoardState::from_transcript(4, "f5a1").unwrap_err(),
+        NotationError::IllegalMove(2, Position::new(0, 0), MoveError::NoFlip)
+    );
+    assert_eq!(
+        BoardState::from_transcript(4, "f5i9").unwrap_err(),
+        NotationError::IllegalMove(2, Position::new(8, 8), MoveError::OutOfBounds)
+    );
+}
@@ -0,0 +1,80 @@
+use simple_reversi_2::{Backend, BoardState};
+
+/// 8x8の初期局面からのperftの既知の値（深さ1から順に）
+const PERFT_8X8: [u64; 8] = [4, 12, 56, 244, 1396, 8200, 55092, 390216];
+
+#[test]
+fn perft_8x8_matches_known_values() {
+    let bs = BoardState::new(4, false);
+    for (d, &expected) in PERFT_8X8.iter().enumerate() {
+        assert_eq!(bs.perft(d + 1), expected, "depth {}", d + 1);
+    }
+}
+
+#[test]
+fn perft_depth_zero_is_one() {
+    assert_eq!(BoardState::new(4, false).perft(0), 1);
+}
+
+#[test]
+fn perft_4x4_counts_every_finished_game() {
+    // 4x4のリバーシの終局までの打ち方は60060通り（パスの手も含めて16手以内に必ず終わるので、
+    // それより深く数えても変わらない）
+    let grid = BoardState::with_backend(2, false, Backend::Grid);
+    assert_eq!(grid.perft(16), 60060);
+    let wide = BoardState::with_backend(2, false, Backend::Wide);
+    assert_eq!(wide.perft(17), 60060);
+}
+
+#[test]
+fn perft_agrees_between_backends() {
+    for &backend in &[Backend::Grid, Backend::Bitboard, Backend::Wide] {
+        let bs = BoardState::with_backend(4, false, backend);
+        assert_eq!(bs.perft(5), PERFT_8X8[4], "{:?}", backend);
+    }
+    for &n in &[3, 5] {
+        let grid = BoardState::with_backend(n, false, Backend::Grid);
+        let wide = BoardState::with_backend(n, false, Backend::Wide);
+        for d in 1..=5 {
+            assert_eq!(
+                grid.perft(d),
+                wide.perft(d),
+                "{}x{} depth {}",
+                2 * n,
+                2 * n,
+                d
+            );
+        }
+    }
+}
+
+#[test]
+fn perft_of_large_boards_matches_8x8_while_edges_are_out_of_reach() {
+    // 5手以内では10x10以上の盤面の端の影響を受けないので8x8と同じ数になる
+    for &n in &[5, 6, 8] {
+        let bs = BoardState::new(n, false);
+        for d in 1..=5 {
+            assert_eq!(
+                bs.perft(d),
+                PERFT_8X8[d - 1],
+                "{}x{} depth {}",
+                2 * n,
+                2 * n,
+                d
+            );
+        }
+    }
+}
+
+#[test]
+fn perft_leaves_the_board_unchanged() {
+    let mut bs = BoardState::new(4, false);
+    bs.put(4, 5);
+    bs.put(5, 5);
+    let board = bs.show_board();
+    let history = bs.history().to_vec();
+    bs.perft(4);
+    assert_eq!(bs.show_board(), board);
+    assert_eq!(bs.history(), &history[..]);
+    assert_eq!(bs.undo().map(|p| (p.x, p.y)), Some((5, 5)));
+}
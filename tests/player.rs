@@ -0,0 +1,27 @@
+use simple_reversi_2::player::{Action, Player, RandomPlayer};
+use simple_reversi_2::BoardState;
+
+/// 黒と白がそれぞれのシードのランダムなCPUで最後まで打ったときの棋譜
+fn random_game(black_seed: u64, white_seed: u64) -> Vec<Action> {
+    let mut players = [
+        RandomPlayer::from_seed(black_seed),
+        RandomPlayer::from_seed(white_seed),
+    ];
+    let mut bs = BoardState::new(4, false);
+    let mut actions = Vec::new();
+    while !bs.is_game_over() {
+        let side = if bs.is_it_white_turn() { 1 } else { 0 };
+        let action = players[side].play(&bs).unwrap();
+        if let Action::Put(pos) = action {
+            bs.put(pos.x, pos.y);
+        }
+        actions.push(action);
+    }
+    actions
+}
+
+#[test]
+fn random_player_is_reproducible_with_the_same_seed() {
+    assert_eq!(random_game(42, 43), random_game(42, 43));
+    assert_ne!(random_game(42, 43), random_game(1, 2));
+}
@@ -0,0 +1,74 @@
+use simple_reversi_2::{BoardState, ParseBoardError, Position, Turn};
+
+#[test]
+fn initial_position_prints_and_parses() {
+    let text = "......../......../......../...o#.../...#o.../......../......../........ #";
+    let bs = BoardState::new(4, false);
+    assert_eq!(bs.to_string(), text);
+
+    let parsed: BoardState = text.parse().unwrap();
+    assert_eq!(parsed.show_board(), bs.show_board());
+    assert_eq!(parsed.turn(), Turn::Black);
+    assert_eq!(parsed.legal_moves(), bs.legal_moves());
+}
+
+#[test]
+fn positions_round_trip_and_can_be_played_on() {
+    let mut bs = BoardState::new(5, false);
+    for _ in 0..7 {
+        let pos = *bs.legal_moves().last().unwrap();
+        bs.put(pos.x, pos.y);
+    }
+    let mut parsed: BoardState = bs.to_string().parse().unwrap();
+    assert_eq!(parsed.to_string(), bs.to_string());
+    assert_eq!(parsed.legal_moves(), bs.legal_moves());
+    assert!(parsed.history().is_empty());
+
+    let pos = parsed.legal_moves()[0];
+    let mut played = bs.clone();
+    assert_eq!(parsed.put(pos.x, pos.y), played.put(pos.x, pos.y));
+    assert_eq!(parsed.to_string(), played.to_string());
+}
+
+#[test]
+fn custom_positions_can_be_set_up() {
+    // 白が置けるのはa1だけで、置くと黒は置けなくなりゲームが終わる
+    let mut bs: BoardState = ".#o./..../..../.... o".parse().unwrap();
+    assert_eq!(bs.legal_moves(), vec![Position::new(0, 0)]);
+    assert!(bs.put(0, 0).game_over);
+    assert_eq!(bs.to_string(), "ooo./..../..../.... o");
+}
+
+#[test]
+fn broken_positions_are_rejected() {
+    let parse = |s: &str| s.parse::<BoardState>().err();
+    assert_eq!(parse("..../.o#./.#o./...."), Some(ParseBoardError::Format));
+    assert_eq!(
+        parse("..../.o#./.#o./.... # x"),
+        Some(ParseBoardError::Format)
+    );
+    assert_eq!(parse("../.. #"), Some(ParseBoardError::Size));
+    assert_eq!(parse("..../.o#./.#o/.... #"), Some(ParseBoardError::Size));
+    assert_eq!(
+        parse("..../.o#./.#x./.... #"),
+        Some(ParseBoardError::Cell(2, 2, 'x'))
+    );
+    assert_eq!(
+        parse("..../.o#./.#o./.... b"),
+        Some(ParseBoardError::Turn("b".to_string()))
+    );
+}
+
+#[test]
+fn positions_where_only_the_opponent_can_move_pass_the_turn() {
+    // 黒は置けず白だけが置けるので、白の番として読む
+    let bs: BoardState = "o#../..../..../.... #".parse().unwrap();
+    assert_eq!(bs.turn(), Turn::White);
+    assert_eq!(bs.legal_moves(), vec![Position::new(0, 2)]);
+    assert!(!bs.is_game_over());
+
+    // どちらも置けない局面は手番をそのままにして終局として読む
+    let bs: BoardState = "o.../..../..../.... #".parse().unwrap();
+    assert_eq!(bs.turn(), Turn::Black);
+    assert!(bs.is_game_over());
+}
@@ -0,0 +1,134 @@
+use simple_reversi_2::player::PlayerKind;
+use simple_reversi_2::render::{char_width, Color, MemoryRenderer, Renderer, Style};
+use simple_reversi_2::screen::{draw_header, draw_result, preview_board, preview_board_with_help};
+use simple_reversi_2::search::Level;
+use simple_reversi_2::BoardState;
+
+fn cursor() -> Style {
+    Style::bold().with_fg(Color::Blue).with_bg(Color::Yellow)
+}
+
+#[test]
+fn wide_characters_take_two_columns() {
+    assert_eq!(char_width('#'), 1);
+    assert_eq!(char_width('盤'), 2);
+    assert_eq!(char_width('，'), 2);
+    assert_eq!(char_width('　'), 2);
+
+    let mut out = MemoryRenderer::new();
+    out.print(0, 0, "盤面：4 x 4", Style::plain()).unwrap();
+    assert_eq!(out.find("4 x 4"), Some((6, 0)));
+
+    // 全角文字の片方だけに書くと、その全角文字の残りは空白になる
+    out.print(1, 0, "ab", Style::bold()).unwrap();
+    assert_eq!(out.line(0), " ab ：4 x 4");
+    assert_eq!(out.style_at(2, 0), Style::bold());
+    assert_eq!(out.style_at(3, 0), Style::plain());
+
+    out.clear_line(0).unwrap();
+    out.print(2, 2, "x", Style::plain()).unwrap();
+    assert_eq!(out.lines(), vec!["", "", "  x"]);
+    out.clear().unwrap();
+    assert_eq!(out.text(), "");
+}
+
+#[test]
+fn board_with_cursor() {
+    let bs = BoardState::new(2, false);
+    let mut out = MemoryRenderer::new();
+    preview_board(&mut out, &bs, 2, 1, 4).unwrap();
+    assert_eq!(
+        out.text(),
+        ["", "", "", "", " . . . .", " . o # .", " . # o .", " . . . ."].join("\n")
+    );
+    // 3行目の2列目のマスがカーソル（1マスにつき2列で、駒は2列目）
+    assert_eq!(out.style_at(3, 6), cursor());
+    assert_eq!(out.style_at(2, 6), Style::plain());
+    assert_eq!(out.style_at(5, 6), Style::plain());
+
+    // カーソルを盤面の外に置くと出ない
+    let mut out = MemoryRenderer::new();
+    preview_board(&mut out, &bs, 4, 4, 0).unwrap();
+    assert!((0..4).all(|row| (0..8).all(|col| out.style_at(col, row) == Style::plain())));
+}
+
+#[test]
+fn hint_overlay_marks_legal_moves() {
+    let bs = BoardState::new(2, false);
+    let mut out = MemoryRenderer::new();
+    preview_board_with_help(&mut out, &bs, 0, 1, 0).unwrap();
+    assert_eq!(
+        out.text(),
+        [" . + . .", " + o # .", " . # o +", " . . + ."].join("\n")
+    );
+    assert_eq!(out.style_at(3, 0), cursor());
+}
+
+#[test]
+fn header_shows_size_mode_and_note() {
+    let mut out = MemoryRenderer::new();
+    out.print(0, 7, "前の画面の残り", Style::plain()).unwrap();
+    draw_header(
+        &mut out,
+        Some(6),
+        Some([PlayerKind::Search(Level::Strong), PlayerKind::Human]),
+        "　観戦中",
+    )
+    .unwrap();
+    assert_eq!(
+        out.text(),
+        [
+            " ===== Simple Reversi =====",
+            "盤面：6 x 6　観戦中",
+            "CPU対戦モード（強い）",
+        ]
+        .join("\n")
+    );
+    assert_eq!(out.style_at(1, 0), Style::bold().with_fg(Color::Red));
+}
+
+#[test]
+fn result_screen() {
+    let bs: BoardState = "####/####/###o/oooo #".parse().unwrap();
+    let mut out = MemoryRenderer::new();
+    draw_result(
+        &mut out,
+        &bs,
+        [PlayerKind::Human, PlayerKind::Search(Level::Normal)],
+        42,
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        out.text(),
+        [
+            " ===== Simple Reversi =====",
+            "盤面：4 x 4",
+            "CPU対戦モード（普通）",
+            "",
+            " # # # #",
+            " # # # #",
+            " # # # o",
+            " o o o o",
+            "",
+            "oが5個，#が11個で#の勝ち！",
+            "シード：42（--seed 42 で同じ対局を再現できます）",
+            "gキーで棋譜をGGF形式で保存できます．終了するにはEnterを押してください．",
+        ]
+        .join("\n")
+    );
+
+    // 通信対戦が途中で切れたときは理由を赤の太字で出す
+    let mut out = MemoryRenderer::new();
+    draw_result(
+        &mut out,
+        &bs,
+        [PlayerKind::Human, PlayerKind::Remote],
+        0,
+        Some("相手がゲームを終了しました．"),
+    )
+    .unwrap();
+    assert_eq!(out.line(2), "通信対戦モード");
+    assert_eq!(out.line(13), "相手がゲームを終了しました．");
+    assert_eq!(out.style_at(0, 13), Style::bold().with_fg(Color::Red));
+}
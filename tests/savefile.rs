@@ -0,0 +1,56 @@
+use simple_reversi_2::player::PlayerKind;
+use simple_reversi_2::savefile::{SaveFileError, SavedGame};
+use simple_reversi_2::search::Level;
+use simple_reversi_2::{BoardState, Move, Turn};
+
+/// 4x4で白がパスする局面まで打った盤面
+fn board_with_pass() -> BoardState {
+    let mut bs = BoardState::new(2, false);
+    for &(x, y) in &[(0, 1), (0, 0), (2, 3), (0, 2), (1, 0), (2, 0)] {
+        bs.put(x, y);
+    }
+    bs
+}
+
+#[test]
+fn saved_game_round_trips_through_text() {
+    let bs = board_with_pass();
+    assert!(bs.history().iter().any(|entry| entry.mv == Move::Pass));
+    let saved = SavedGame::new(&bs, PlayerKind::Human, PlayerKind::Search(Level::Strong));
+
+    let parsed: SavedGame = saved.to_string().parse().unwrap();
+    assert_eq!(parsed, saved);
+
+    let restored = parsed.to_board().unwrap();
+    assert_eq!(restored.show_board(), bs.show_board());
+    assert_eq!(restored.turn(), bs.turn());
+    assert_eq!(restored.history(), bs.history());
+}
+
+#[test]
+fn saved_game_rejects_broken_files() {
+    let text = "simple-reversi-2 save\nsize 8\nblack human\nwhite random\nturn black\n";
+    assert!(text.parse::<SavedGame>().is_ok());
+
+    let missing_turn = text.replace("turn black\n", "");
+    assert!(matches!(
+        missing_turn.parse::<SavedGame>(),
+        Err(SaveFileError::Missing("turn"))
+    ));
+    assert!(matches!(
+        text.replace("size 8", "size 7").parse::<SavedGame>(),
+        Err(SaveFileError::Syntax(2, _))
+    ));
+
+    let illegal: SavedGame = format!("{}move 0 0\n", text).parse().unwrap();
+    assert!(matches!(
+        illegal.to_board(),
+        Err(SaveFileError::IllegalMove(1, _))
+    ));
+    let wrong_turn: SavedGame = text.replace("turn black", "turn white").parse().unwrap();
+    assert!(matches!(
+        wrong_turn.to_board(),
+        Err(SaveFileError::TurnMismatch)
+    ));
+    assert_eq!(wrong_turn.turn, Turn::White);
+}
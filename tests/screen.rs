@@ -0,0 +1,367 @@
+use std::net::TcpListener;
+use std::thread;
+use std::time::Duration;
+use std::{env, fs, io, process};
+
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::ErrorKind;
+
+use simple_reversi_2::input::{InputSource, Key, ScriptedInput};
+use simple_reversi_2::netplay::{accept_game, join_game, Message};
+use simple_reversi_2::render::{Color, MemoryRenderer, Style};
+use simple_reversi_2::screen::{run_with, show_result, Session};
+use simple_reversi_2::{BoardState, Turn};
+
+fn key(code: KeyCode, modifiers: KeyModifiers) -> Event {
+    Event::Key(KeyEvent::new(code, modifiers))
+}
+
+#[test]
+fn quit_keys_work_everywhere() {
+    for event in [
+        key(KeyCode::Char('c'), KeyModifiers::CONTROL),
+        key(KeyCode::Esc, KeyModifiers::NONE),
+        key(KeyCode::Char('q'), KeyModifiers::NONE),
+    ]
+    .iter()
+    {
+        assert_eq!(Key::from_event(event), Some(Key::Quit));
+    }
+}
+
+#[test]
+fn keys_used_by_the_screens() {
+    let cases = [
+        (KeyCode::Up, Key::Up),
+        (KeyCode::Down, Key::Down),
+        (KeyCode::Left, Key::Left),
+        (KeyCode::Right, Key::Right),
+        (KeyCode::Enter, Key::Enter),
+        (KeyCode::Backspace, Key::Backspace),
+        (KeyCode::Char('8'), Key::Char('8')),
+        (KeyCode::Char('u'), Key::Char('u')),
+    ];
+    for &(code, expected) in cases.iter() {
+        assert_eq!(
+            Key::from_event(&key(code, KeyModifiers::NONE)),
+            Some(expected)
+        );
+    }
+
+    // Ctrl-C以外の修飾キーつきの文字やウィンドウのサイズの変更は使わない
+    assert_eq!(
+        Key::from_event(&key(KeyCode::Char('u'), KeyModifiers::CONTROL)),
+        None
+    );
+    assert_eq!(
+        Key::from_event(&key(KeyCode::Char('u'), KeyModifiers::ALT)),
+        None
+    );
+    assert_eq!(Key::from_event(&Event::Resize(80, 24)), None);
+    assert_eq!(
+        Key::from_event(&key(KeyCode::Tab, KeyModifiers::NONE)),
+        None
+    );
+}
+
+/// このテスト用の一時ファイルを使い、CPUの乱数のシードを固定した設定
+fn session(name: &str) -> Session {
+    let file = |ext: &str| {
+        env::temp_dir().join(format!(
+            "simple-reversi-2-{}-{}.{}",
+            process::id(),
+            name,
+            ext
+        ))
+    };
+    Session {
+        save_file: file("save"),
+        ggf_file: file("ggf"),
+        ..Session::new(7)
+    }
+}
+
+/// 最初からkeysの通りに画面を操作し、キーを使い切ったときの画面を返す
+fn screen_after(session: Session, keys: &[Key]) -> MemoryRenderer {
+    let mut out = MemoryRenderer::new();
+    match run_with(session, &mut out, &mut ScriptedInput::new(keys.to_vec())) {
+        Err(ErrorKind::IoError(e)) if e.kind() == io::ErrorKind::UnexpectedEof => out,
+        result => panic!(
+            "キーを使い切る前に終わりました：{:?}\n{}",
+            result,
+            out.text()
+        ),
+    }
+}
+
+/// 盤面のサイズを入力してモードを選ぶキー（itemは上から何番目のモードか）
+fn start(size: usize, item: usize) -> Vec<Key> {
+    let mut keys: Vec<Key> = size.to_string().chars().map(Key::Char).collect();
+    keys.push(Key::Enter);
+    keys.extend(vec![Key::Down; item]);
+    keys.push(Key::Enter);
+    keys
+}
+
+fn selected() -> Style {
+    Style::bold().with_fg(Color::Blue)
+}
+
+#[test]
+fn scripted_input_reads_keys_in_order() {
+    let events = [
+        key(KeyCode::Char('4'), KeyModifiers::NONE),
+        Event::Resize(80, 24),
+        key(KeyCode::Enter, KeyModifiers::NONE),
+        key(KeyCode::Esc, KeyModifiers::NONE),
+    ];
+    let mut input = ScriptedInput::from_events(events.iter());
+    assert_eq!(input.remaining(), 3);
+    assert_eq!(input.read_key().unwrap(), Key::Char('4'));
+    // 待つときは終了するキー以外は読まない
+    assert!(!input.wait(Duration::from_secs(10)).unwrap());
+    assert_eq!(input.read_key().unwrap(), Key::Enter);
+    assert!(input.wait(Duration::from_secs(10)).unwrap());
+    assert!(input.read_key().is_err());
+
+    let mut input = ScriptedInput::default();
+    input.type_text("10\n").push(Key::Quit);
+    assert_eq!(input.remaining(), 4);
+    assert_eq!(input.read_key().unwrap(), Key::Char('1'));
+    assert_eq!(input.read_key().unwrap(), Key::Char('0'));
+    assert_eq!(input.read_key().unwrap(), Key::Enter);
+}
+
+#[test]
+fn size_input_and_menu_selection() {
+    let typed = |text: &str| text.chars().map(Key::Char).collect::<Vec<_>>();
+    let mut keys = typed("3");
+    keys.push(Key::Enter);
+    let out = screen_after(session("size"), &keys);
+    assert_eq!(out.line(3), "入力が不適切です．");
+
+    let mut keys = typed("x");
+    keys.push(Key::Enter);
+    let out = screen_after(session("size"), &keys);
+    assert_eq!(out.line(3), "半角数字で整数を入力してください．");
+
+    // CPU対戦モードの強さは←→キーで選ぶ
+    let mut keys = typed("6");
+    keys.extend(&[Key::Enter, Key::Right]);
+    let out = screen_after(session("menu"), &keys);
+    assert_eq!(out.line(1), "盤面：6 x 6");
+    assert_eq!(out.line(3), "CPU対戦モード ← 強い →");
+    assert_eq!(out.style_at(0, 3), selected());
+    assert_eq!(out.style_at(0, 4), Style::plain());
+
+    keys.push(Key::Down);
+    let out = screen_after(session("menu"), &keys);
+    assert_eq!(out.line(3), "CPU対戦モード（強い）");
+    assert_eq!(out.line(4), "観戦モード");
+    assert_eq!(out.style_at(0, 4), selected());
+
+    // 白を選ぶとCPUが先に打つ
+    let mut keys = start(4, 0);
+    keys.extend(&[Key::Right, Key::Enter]);
+    let out = screen_after(session("menu"), &keys);
+    assert_eq!(out.line(2), "CPU対戦モード（普通）");
+    assert!(out.line(3).starts_with("oのターン．"));
+    assert_eq!(out.text().matches('#').count(), 4);
+}
+
+#[test]
+fn cursor_moves_and_hints_toggle() {
+    let mut keys = start(4, 2);
+    keys.extend(&[Key::Down, Key::Right]);
+    let out = screen_after(session("cursor"), &keys);
+    assert_eq!(out.line(2), "1人2役モード");
+    assert!(out.line(3).starts_with("#のターン．"));
+    // 2行目の2列目（盤面は4行目から、1マスにつき2列）
+    assert_eq!(out.line(5), " . o # .");
+    assert_eq!(out.style_at(3, 5).bg, Some(Color::Yellow));
+    assert_eq!(out.style_at(1, 4), Style::plain());
+
+    // 盤面の下の項目でEnterキーを押すとヒントが出る
+    keys.extend(&[Key::Down, Key::Down, Key::Down]);
+    let out = screen_after(session("cursor"), &keys);
+    assert_eq!(out.line(8), "駒が置ける場所のヒントを見る");
+    assert_eq!(out.style_at(0, 8), selected());
+    keys.push(Key::Enter);
+    let out = screen_after(session("cursor"), &keys);
+    assert_eq!(
+        (4..8).map(|row| out.line(row)).collect::<Vec<_>>(),
+        [" . + . .", " + o # .", " . # o +", " . . + ."]
+    );
+
+    // 置くとヒントは消え、置けないマス目ではメッセージが出る
+    keys.extend(&[Key::Up, Key::Up, Key::Up, Key::Left, Key::Enter]);
+    let out = screen_after(session("cursor"), &keys);
+    assert!(out.line(3).starts_with("oのターン．"));
+    assert_eq!(out.line(4), " . . . .");
+    assert_eq!(out.line(5), " # # # .");
+    keys.extend(&[Key::Right, Key::Right, Key::Right, Key::Enter]);
+    let out = screen_after(session("cursor"), &keys);
+    assert!(out.line(3).starts_with("oのターン．"));
+    assert_eq!(out.line(10), "そこには置けません");
+    assert_eq!(out.style_at(0, 10).fg, Some(Color::Red));
+}
+
+#[test]
+fn quit_confirmation() {
+    let mut keys = start(4, 2);
+    keys.extend(vec![Key::Down; 5]);
+    keys.push(Key::Enter);
+    let out = screen_after(session("confirm"), &keys);
+    assert_eq!(out.line(5), "本当に終了しますか？");
+    assert_eq!(out.find("はい"), Some((2, 7)));
+    assert_eq!(out.style_at(2, 7), selected());
+
+    // 「いいえ」なら対局に戻る
+    keys.extend(&[Key::Right, Key::Enter]);
+    let out = screen_after(session("confirm"), &keys);
+    assert!(out.line(3).starts_with("#のターン．"));
+    assert_eq!(out.style_at(0, 9), selected());
+
+    // 「はい」なら結果の画面に移り、Enterキーで終わる
+    keys.extend(&[Key::Enter, Key::Enter]);
+    let out = screen_after(session("confirm"), &keys);
+    assert_eq!(out.line(9), show_result(&BoardState::new(2, false)));
+    keys.push(Key::Enter);
+    let mut input = ScriptedInput::new(keys);
+    run_with(session("confirm"), &mut MemoryRenderer::new(), &mut input).unwrap();
+    assert_eq!(input.remaining(), 0);
+}
+
+#[test]
+fn quit_key_interrupts_a_cpu_game() {
+    let mut keys = start(8, 1);
+    keys.push(Key::Quit);
+    let mut out = MemoryRenderer::new();
+    run_with(
+        session("interrupt"),
+        &mut out,
+        &mut ScriptedInput::new(keys),
+    )
+    .unwrap();
+    assert_eq!(out.line(2), "観戦モード");
+}
+
+#[test]
+fn quit_key_interrupts_waiting_for_a_remote_move() {
+    // 待ち受けた側が白なので、黒の相手の手を待っているところで終了する
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let host = thread::spawn(move || accept_game(&listener, 4, Turn::White).unwrap());
+    let mut guest = join_game(addr).unwrap();
+    let session = Session {
+        net: Some(host.join().unwrap()),
+        ..session("remote")
+    };
+    let mut input = ScriptedInput::new(vec![Key::Quit]);
+    run_with(session, &mut MemoryRenderer::new(), &mut input).unwrap();
+    assert_eq!(input.remaining(), 0);
+    assert_eq!(guest.connection.recv().unwrap(), Message::Quit);
+}
+
+#[test]
+fn quit_key_on_the_human_turn_tells_the_remote_player() {
+    // 待ち受けた側が黒なので、こちらの番で終了する
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let host = thread::spawn(move || accept_game(&listener, 4, Turn::Black).unwrap());
+    let mut guest = join_game(addr).unwrap();
+    let session = Session {
+        net: Some(host.join().unwrap()),
+        ..session("remote-human")
+    };
+    let mut out = MemoryRenderer::new();
+    let mut input = ScriptedInput::new(vec![Key::Down, Key::Quit]);
+    run_with(session, &mut out, &mut input).unwrap();
+    assert_eq!(input.remaining(), 0);
+    assert_eq!(out.line(2), "通信対戦モード");
+    assert_eq!(out.style_at(1, 5).bg, Some(Color::Yellow));
+    assert_eq!(guest.connection.recv().unwrap(), Message::Quit);
+}
+
+/// 盤面のカーソルをfromからtoまで動かすキー
+fn moves_to(from: (usize, usize), to: (usize, usize)) -> Vec<Key> {
+    let vertical = if to.0 > from.0 { Key::Down } else { Key::Up };
+    let horizontal = if to.1 > from.1 { Key::Right } else { Key::Left };
+    let mut keys = vec![vertical; (to.0 as isize - from.0 as isize).unsigned_abs()];
+    keys.extend(vec![
+        horizontal;
+        (to.1 as isize - from.1 as isize).unsigned_abs()
+    ]);
+    keys
+}
+
+#[test]
+fn full_game_through_to_the_result() {
+    // 1人2役モードで、どちらも左上から探して最初に置けるマス目に打ち続ける
+    let mut keys = start(4, 2);
+    let mut bs = BoardState::new(2, false);
+    let mut cursors = [(0, 0); 2];
+    while !bs.is_game_over() {
+        let side = if bs.is_it_white_turn() { 1 } else { 0 };
+        let cnt = bs.cnt_reversable();
+        let target = (0..4)
+            .flat_map(|x| (0..4).map(move |y| (x, y)))
+            .find(|&(x, y)| cnt[x][y] > 0)
+            .unwrap();
+        keys.extend(moves_to(cursors[side], target));
+        keys.push(Key::Enter);
+        cursors[side] = target;
+        bs.try_put(target.0, target.1).unwrap();
+    }
+
+    let out = screen_after(session("full"), &keys);
+    assert_eq!(out.line(9), show_result(&bs));
+    assert_eq!(
+        out.line(10),
+        "シード：7（--seed 7 で同じ対局を再現できます）"
+    );
+    let board: Vec<String> = bs
+        .show_board()
+        .iter()
+        .map(|row| row.iter().map(|c| format!(" {}", c)).collect())
+        .collect();
+    assert_eq!((4..8).map(|row| out.line(row)).collect::<Vec<_>>(), board);
+
+    // gキーで棋譜を書き足してからEnterキーで終わる
+    let session = session("full");
+    let ggf_file = session.ggf_file.clone();
+    fs::remove_file(&ggf_file).ok();
+    keys.extend(&[Key::Char('g'), Key::Enter]);
+    let mut out = MemoryRenderer::new();
+    run_with(session, &mut out, &mut ScriptedInput::new(keys)).unwrap();
+    assert_eq!(
+        out.line(12),
+        format!("{}に棋譜を保存しました．", ggf_file.display())
+    );
+    let ggf = fs::read_to_string(&ggf_file).unwrap();
+    fs::remove_file(&ggf_file).unwrap();
+    assert_eq!(ggf.lines().count(), 1);
+    assert!(ggf.starts_with("(;GM[Othello]"));
+}
+
+#[test]
+fn saved_game_can_be_resumed() {
+    let save_file = session("resume").save_file;
+    fs::remove_file(&save_file).ok();
+    let mut keys = start(4, 2);
+    keys.extend(&[Key::Right, Key::Enter, Key::Char('s')]);
+    let out = screen_after(session("resume"), &keys);
+    assert_eq!(
+        out.line(10),
+        format!("{}に保存しました．", save_file.display())
+    );
+
+    // 次に起動すると再開するかどうかを選べる
+    let out = screen_after(session("resume"), &[]);
+    assert_eq!(out.line(3), "保存したゲームを再開する");
+    assert_eq!(out.style_at(0, 3), selected());
+    let out = screen_after(session("resume"), &[Key::Enter]);
+    fs::remove_file(&save_file).unwrap();
+    assert!(out.line(3).starts_with("oのターン．"));
+    assert_eq!(out.line(4), " . # . .");
+}
@@ -0,0 +1,173 @@
+use rand::rngs::SmallRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+use simple_reversi_2::search::{disc_difference, evaluate, search, SearchLimits};
+use simple_reversi_2::BoardState;
+
+/// 勝ちが確定した局面の点数（search.rsと同じ値）
+const WIN_SCORE: i32 = 1_000_000;
+
+/// 終局まで読み切らず、局面の数でも打ち切らない条件
+fn depth_only(depth: usize) -> SearchLimits {
+    SearchLimits {
+        depth,
+        nodes: None,
+        endgame_empties: 0,
+    }
+}
+
+/// 枝刈りをしない素朴なミニマックス法で、手番の側から見たdepth手読みの点数を求める
+///
+/// パスは手数に数えず、終局した局面は石差に勝ち負けの点数を足したものにする。
+fn minimax(bs: &BoardState, depth: usize) -> i32 {
+    if bs.is_game_over() {
+        let diff = disc_difference(bs, bs.turn());
+        return match diff {
+            d if d > 0 => WIN_SCORE + d,
+            d if d < 0 => -WIN_SCORE + d,
+            _ => 0,
+        };
+    }
+    if depth == 0 {
+        return evaluate(bs, bs.turn());
+    }
+    bs.legal_moves()
+        .into_iter()
+        .map(|pos| score_after(bs, pos.x, pos.y, depth))
+        .max()
+        .unwrap()
+}
+
+/// (x, y)に置いた後の局面をdepth - 1手読んだ点数（置いた側から見た値）
+fn score_after(bs: &BoardState, x: usize, y: usize, depth: usize) -> i32 {
+    let mut next = bs.clone();
+    next.put(x, y);
+    // 相手がパスになった（または終局した）ときは手番が変わらない
+    if next.turn() == bs.turn() {
+        minimax(&next, depth - 1)
+    } else {
+        -minimax(&next, depth - 1)
+    }
+}
+
+/// 初期局面からplies手ランダムに打ち進めた局面（途中で終局したらNone）
+fn random_position(n: usize, plies: usize, rng: &mut SmallRng) -> Option<BoardState> {
+    let mut bs = BoardState::new(n, false);
+    for _ in 0..plies {
+        let pos = *bs.legal_moves().choose(rng)?;
+        bs.put(pos.x, pos.y);
+    }
+    if bs.is_game_over() {
+        None
+    } else {
+        Some(bs)
+    }
+}
+
+/// searchの結果がミニマックス法と一致するかを確かめる
+fn assert_matches_minimax(bs: &BoardState, depth: usize) {
+    let result = search(bs, depth_only(depth));
+    // 勝ち負けが読み切れたらそこで止まるので、読めた深さで比べる
+    assert!(result.depth >= 1 && result.depth <= depth);
+    if result.depth < depth {
+        assert!(result.score.abs() >= WIN_SCORE, "{}", bs);
+    }
+    assert_eq!(result.score, minimax(bs, result.depth), "{}", bs);
+
+    // 選んだ手もその点数になる
+    let pos = result.best_move.unwrap();
+    assert_eq!(
+        score_after(bs, pos.x, pos.y, result.depth),
+        result.score,
+        "{}",
+        bs
+    );
+}
+
+#[test]
+fn search_matches_minimax_on_4x4() {
+    let mut rng = SmallRng::seed_from_u64(8);
+    for plies in 0..8 {
+        let bs = match random_position(2, plies, &mut rng) {
+            Some(bs) => bs,
+            None => continue,
+        };
+        for depth in 1..=4 {
+            assert_matches_minimax(&bs, depth);
+        }
+    }
+}
+
+#[test]
+fn search_matches_minimax_on_6x6() {
+    let mut rng = SmallRng::seed_from_u64(6);
+    for plies in [0, 4, 10, 18].iter() {
+        let bs = match random_position(3, *plies, &mut rng) {
+            Some(bs) => bs,
+            None => continue,
+        };
+        for depth in 1..=3 {
+            assert_matches_minimax(&bs, depth);
+        }
+    }
+}
+
+#[test]
+fn search_reads_through_a_forced_pass() {
+    // 相手をパスさせる手がある局面を探す
+    let mut rng = SmallRng::seed_from_u64(2021);
+    let bs = loop {
+        let bs = match random_position(2, 6, &mut rng) {
+            Some(bs) => bs,
+            None => continue,
+        };
+        let forces_pass = bs.legal_moves().into_iter().any(|pos| {
+            let mut next = bs.clone();
+            let outcome = next.put(pos.x, pos.y);
+            outcome.opponent_passed
+        });
+        if forces_pass {
+            break bs;
+        }
+    };
+    for depth in 1..=4 {
+        assert_matches_minimax(&bs, depth);
+    }
+}
+
+#[test]
+fn search_stops_at_the_node_limit() {
+    let bs = BoardState::new(3, false);
+    let full = search(&bs, depth_only(8));
+    assert_eq!(full.depth, 8);
+
+    let limit = full.nodes / 4;
+    let limits = SearchLimits {
+        depth: 8,
+        nodes: Some(limit),
+        endgame_empties: 0,
+    };
+    let result = search(&bs, limits);
+    assert!(result.depth >= 1 && result.depth < 8);
+    assert!(result.nodes > limit);
+    assert!(bs.legal_moves().contains(&result.best_move.unwrap()));
+
+    // 打ち切られた深さの手前までの結果は制限なしで読んだときと同じ
+    let shallower = search(&bs, depth_only(result.depth));
+    assert_eq!(result.best_move, shallower.best_move);
+    assert_eq!(result.score, shallower.score);
+}
+
+#[test]
+fn search_finishes_depth_one_even_with_a_tiny_node_limit() {
+    let bs = BoardState::new(4, false);
+    let limits = SearchLimits {
+        depth: 6,
+        nodes: Some(1),
+        endgame_empties: 0,
+    };
+    let result = search(&bs, limits);
+    assert_eq!(result.depth, 1);
+    assert!(bs.legal_moves().contains(&result.best_move.unwrap()));
+}
@@ -0,0 +1,106 @@
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+use simple_reversi_2::spectate::{SpectateError, SpectatorClient, SpectatorServer, Update};
+use simple_reversi_2::{BoardState, Position};
+
+/// 観戦者の数がcountになるまで待つ（接続は別のスレッドで受け付けるため）
+fn wait_for_spectators(server: &SpectatorServer, count: usize) {
+    let start = Instant::now();
+    while server.spectators() < count {
+        assert!(start.elapsed() < Duration::from_secs(5), "観戦者が来ません");
+        sleep(Duration::from_millis(10));
+    }
+}
+
+#[test]
+fn updates_read_back_the_same() {
+    let mut bs = BoardState::new(2, false);
+    let update = Update::new(&bs);
+    assert_eq!(update.to_string(), format!("{} -", bs));
+    assert_eq!(update.last_move, None);
+
+    let pos = bs.legal_moves()[0];
+    bs.put(pos.x, pos.y);
+    let update = Update::new(&bs);
+    assert_eq!(update.last_move, Some(pos));
+    assert_eq!(update.board.history().len(), 0);
+    let read: Update = update.to_string().parse().unwrap();
+    assert_eq!(read.to_string(), update.to_string());
+
+    assert!(matches!(
+        "nonsense".parse::<Update>(),
+        Err(SpectateError::Format(_))
+    ));
+    assert!(matches!(
+        "..../.... # f5".parse::<Update>(),
+        Err(SpectateError::Board(_))
+    ));
+    assert!(matches!(
+        format!("{} 5f", bs).parse::<Update>(),
+        Err(SpectateError::LastMove(_))
+    ));
+}
+
+#[test]
+fn updates_can_be_made_for_any_board_size() {
+    // 2x2の盤面は文字列からは読めないが、送る局面は作れる
+    let bs = BoardState::new(1, false);
+    let update = Update::new(&bs);
+    assert_eq!(update.board.get_size(), 2);
+    assert_eq!(update.to_string(), format!("{} -", bs));
+}
+
+#[test]
+fn spectators_receive_every_new_position() {
+    let server = SpectatorServer::bind("127.0.0.1:0").unwrap();
+    let mut bs = BoardState::new(4, false);
+    server.broadcast(&bs);
+
+    // 途中から来た観戦者にも最後の局面が届く
+    let mut early = SpectatorClient::connect(server.local_addr()).unwrap();
+    wait_for_spectators(&server, 1);
+    assert_eq!(early.next().unwrap().unwrap().last_move, None);
+
+    let mut moves: Vec<Position> = Vec::new();
+    for _ in 0..3 {
+        let pos = bs.legal_moves()[0];
+        bs.put(pos.x, pos.y);
+        moves.push(pos);
+        server.broadcast(&bs);
+        // 同じ局面は2回送らない
+        server.broadcast(&bs);
+    }
+    let late = SpectatorClient::connect(server.local_addr()).unwrap();
+    wait_for_spectators(&server, 2);
+    drop(server);
+
+    let received: Vec<Update> = early.map(|u| u.unwrap()).collect();
+    assert_eq!(received.len(), 3);
+    for (update, &pos) in received.iter().zip(&moves) {
+        assert_eq!(update.last_move, Some(pos));
+    }
+    assert_eq!(received[2].board.to_string(), bs.to_string());
+
+    let received: Vec<Update> = late.map(|u| u.unwrap()).collect();
+    assert_eq!(received.len(), 1);
+    assert_eq!(received[0].board.to_string(), bs.to_string());
+}
+
+#[test]
+fn disconnected_spectators_are_dropped() {
+    let server = SpectatorServer::bind("127.0.0.1:0").unwrap();
+    let client = SpectatorClient::connect(server.local_addr()).unwrap();
+    wait_for_spectators(&server, 1);
+    drop(client);
+
+    // 切れた接続への送信は1回目は通ることがあるので、何手か送る
+    let mut bs = BoardState::new(4, false);
+    for _ in 0..4 {
+        let pos = bs.legal_moves()[0];
+        bs.put(pos.x, pos.y);
+        server.broadcast(&bs);
+        sleep(Duration::from_millis(20));
+    }
+    assert_eq!(server.spectators(), 0);
+}
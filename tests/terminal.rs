@@ -0,0 +1,19 @@
+use std::panic;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use simple_reversi_2::screen::TerminalGuard;
+
+#[test]
+fn guard_puts_back_the_previous_panic_hook() {
+    let called = Arc::new(AtomicBool::new(false));
+    let flag = Arc::clone(&called);
+    panic::set_hook(Box::new(move |_| flag.store(true, Ordering::SeqCst)));
+
+    // ターミナルがない環境では入るのに失敗するが、そのときもガードは捨てられる
+    drop(TerminalGuard::enter(false));
+
+    assert!(panic::catch_unwind(|| panic!("フックの確認")).is_err());
+    drop(panic::take_hook());
+    assert!(called.load(Ordering::SeqCst));
+}
@@ -0,0 +1,107 @@
+use std::io;
+
+use simple_reversi_2::player::{Action, Player, PlayerKind, RandomPlayer};
+use simple_reversi_2::search::Level;
+use simple_reversi_2::tournament::{cpu_kind, play_game, MoveTimes, Tournament, TournamentError};
+use simple_reversi_2::{BoardState, MoveError, Position};
+
+fn random_tournament(games: usize, seed: u64) -> Tournament {
+    Tournament {
+        size: 4,
+        games,
+        players: [PlayerKind::Random, PlayerKind::Random],
+        seed,
+    }
+}
+
+#[test]
+fn plays_every_game_and_alternates_colors() {
+    let mut outcomes = Vec::new();
+    let stats = random_tournament(10, 7)
+        .run(|game, outcome| outcomes.push((game, outcome.clone())))
+        .unwrap();
+
+    assert_eq!(stats.games, 10);
+    assert_eq!(stats.wins + stats.losses + stats.draws, 10);
+    assert_eq!(outcomes.len(), 10);
+    for (k, (game, outcome)) in outcomes.iter().enumerate() {
+        assert_eq!(*game, k);
+        assert_eq!(outcome.first_is_black, k % 2 == 0);
+        assert!(outcome.board.is_game_over());
+    }
+    let total: i64 = outcomes.iter().map(|(_, o)| o.disc_diff as i64).sum();
+    assert_eq!(stats.disc_diff_total, total);
+    assert!((stats.average_disc_diff() - total as f64 / 10.0).abs() < 1e-9);
+
+    // 打った手の数は盤面の履歴と合う（パスは数えない）
+    let moves: u64 = stats.times.iter().map(|t| t.moves).sum();
+    let placed: usize = outcomes
+        .iter()
+        .map(|(_, o)| o.board.transcript().len() / 2)
+        .sum();
+    assert_eq!(moves, placed as u64);
+}
+
+#[test]
+fn same_seed_gives_the_same_results() {
+    let a = random_tournament(6, 42).run(|_, _| {}).unwrap();
+    let b = random_tournament(6, 42).run(|_, _| {}).unwrap();
+    assert_eq!(
+        (a.wins, a.losses, a.draws, a.disc_diff_total),
+        (b.wins, b.losses, b.draws, b.disc_diff_total)
+    );
+}
+
+#[test]
+fn search_beats_random_most_of_the_time() {
+    let tournament = Tournament {
+        size: 6,
+        games: 10,
+        players: [PlayerKind::Search(Level::Weak), PlayerKind::Random],
+        seed: 1,
+    };
+    let stats = tournament.run(|_, _| {}).unwrap();
+    assert!(stats.wins > stats.losses);
+    assert!(stats.average_disc_diff() > 0.0);
+}
+
+#[test]
+fn rejects_players_that_are_not_cpus() {
+    let tournament = Tournament {
+        players: [PlayerKind::Random, PlayerKind::Human],
+        ..random_tournament(2, 0)
+    };
+    assert!(matches!(
+        tournament.run(|_, _| {}),
+        Err(TournamentError::NotCpu(PlayerKind::Human))
+    ));
+    assert_eq!(cpu_kind("strong"), Some(PlayerKind::Search(Level::Strong)));
+    assert_eq!(cpu_kind("human"), None);
+}
+
+/// いつも同じ行動を返す指し手
+struct Fixed(Action);
+
+impl Player for Fixed {
+    fn play(&mut self, _: &BoardState) -> io::Result<Action> {
+        Ok(self.0)
+    }
+}
+
+#[test]
+fn reports_players_that_misbehave() {
+    let mut black = Fixed(Action::Put(Position::new(0, 0)));
+    let mut white = RandomPlayer::from_seed(0);
+    let (mut tb, mut tw) = (MoveTimes::default(), MoveTimes::default());
+    assert!(matches!(
+        play_game(3, 8, [&mut black, &mut white], [&mut tb, &mut tw]),
+        Err(TournamentError::IllegalMove(3, _, MoveError::NoFlip))
+    ));
+    assert_eq!(tb.moves, 1);
+
+    let mut black = Fixed(Action::Undo);
+    assert!(matches!(
+        play_game(1, 8, [&mut black, &mut white], [&mut tb, &mut tw]),
+        Err(TournamentError::UnexpectedAction(1, Action::Undo))
+    ));
+}
@@ -0,0 +1,134 @@
+use simple_reversi_2::player::RandomPlayer;
+use simple_reversi_2::wthor::{WthorError, WthorHeader, WthorReader};
+use simple_reversi_2::{BoardState, Move, Position};
+
+/// 対局数だけを書いたヘッダー（2024年1月2日作成、2023年の対局、8x8）
+fn header(games: u32) -> Vec<u8> {
+    let mut bytes = vec![20, 24, 1, 2];
+    bytes.extend_from_slice(&games.to_le_bytes());
+    bytes.extend_from_slice(&[0, 0]);
+    bytes.extend_from_slice(&2023u16.to_le_bytes());
+    bytes.extend_from_slice(&[8, 0, 22, 0]);
+    bytes
+}
+
+/// 着手の列を対局1つ分の記録にする（番号は適当、60手に足りない分は0で埋める）
+fn record(moves: &[Position], black_score: u8) -> Vec<u8> {
+    let mut bytes = vec![1, 0, 2, 0, 3, 0, black_score, black_score];
+    bytes.extend(moves.iter().map(|pos| (10 * (pos.x + 1) + pos.y + 1) as u8));
+    bytes.resize(68, 0);
+    bytes
+}
+
+/// シードを指定したランダムなCPU同士の8x8の1局
+fn random_game(seed: u64) -> BoardState {
+    let mut player = RandomPlayer::from_seed(seed);
+    let mut bs = BoardState::new(4, false);
+    while let Some(pos) = player.choose(&bs) {
+        bs.put(pos.x, pos.y);
+    }
+    bs
+}
+
+/// 盤面の履歴から置いた手だけを取り出す（WTHORはパスを記録しない）
+fn moves_of(bs: &BoardState) -> Vec<Position> {
+    bs.history()
+        .iter()
+        .filter_map(|entry| match entry.mv {
+            Move::Put(pos) => Some(pos),
+            Move::Pass => None,
+        })
+        .collect()
+}
+
+fn black_count(bs: &BoardState) -> u8 {
+    let (_, (_, black)) = bs.count_pieces();
+    black as u8
+}
+
+#[test]
+fn reads_the_header_and_replays_each_game() {
+    let games: Vec<BoardState> = (0..3).map(random_game).collect();
+    let mut bytes = header(games.len() as u32);
+    for bs in &games {
+        let moves = moves_of(bs);
+        bytes.extend(record(&moves, black_count(bs)));
+    }
+
+    let reader = WthorReader::new(&bytes[..]).unwrap();
+    assert_eq!(
+        reader.header(),
+        WthorHeader {
+            created: (2024, 1, 2),
+            games: 3,
+            year: 2023,
+            depth: 22,
+        }
+    );
+    let read: Vec<_> = reader.map(|game| game.unwrap()).collect();
+    assert_eq!(read.len(), 3);
+    for (game, bs) in read.iter().zip(&games) {
+        assert_eq!(game.tournament, 1);
+        assert_eq!(game.black_player, 2);
+        assert_eq!(game.white_player, 3);
+        assert_eq!(game.black_score, black_count(bs));
+        assert_eq!(game.board.transcript(), bs.transcript());
+        assert!(game.board.is_game_over());
+    }
+}
+
+#[test]
+fn skips_games_with_illegal_moves() {
+    let good = random_game(7);
+    let moves = moves_of(&good);
+    let mut bytes = header(3);
+    // 1局目は2手目が打てない、2局目は盤面の外
+    bytes.extend(record(&[Position::new(4, 5), Position::new(0, 0)], 0));
+    let mut bad_square = record(&moves, 0);
+    bad_square[10] = 19;
+    bytes.extend(bad_square);
+    bytes.extend(record(&moves, black_count(&good)));
+
+    let read: Vec<_> = WthorReader::new(&bytes[..]).unwrap().collect();
+    assert_eq!(read.len(), 3);
+    match &read[0] {
+        Err(WthorError::IllegalMove(1, 2, pos)) => assert_eq!(*pos, Position::new(0, 0)),
+        r => panic!("{:?}", r),
+    }
+    match &read[1] {
+        Err(WthorError::BadSquare(2, 3, 19)) => {}
+        r => panic!("{:?}", r),
+    }
+    assert_eq!(
+        read[2].as_ref().unwrap().board.transcript(),
+        good.transcript()
+    );
+}
+
+#[test]
+fn stops_at_a_truncated_file() {
+    let moves = moves_of(&random_game(1));
+    let mut bytes = header(2);
+    bytes.extend(record(&moves, 0));
+    bytes.extend(&record(&moves, 0)[..30]);
+
+    let read: Vec<_> = WthorReader::new(&bytes[..]).unwrap().collect();
+    assert_eq!(read.len(), 2);
+    assert!(read[0].is_ok());
+    assert!(matches!(read[1], Err(WthorError::Io(_))));
+
+    assert!(matches!(
+        WthorReader::new(&header(0)[..10]),
+        Err(WthorError::Io(_))
+    ));
+}
+
+#[test]
+fn rejects_other_board_sizes() {
+    let mut bytes = header(0);
+    bytes[12] = 10;
+    assert!(matches!(
+        WthorReader::new(&bytes[..]),
+        Err(WthorError::Unsupported)
+    ));
+}